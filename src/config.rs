@@ -0,0 +1,109 @@
+use serde::Deserialize;
+use std::{env, fs, path::Path};
+
+use crate::error::Error;
+
+pub(crate) const API_KEY_ENV: &str = "LATITUDE_API_KEY";
+pub(crate) const PROJECT_ID_ENV: &str = "LATITUDE_PROJECT_ID";
+pub(crate) const VERSION_ID_ENV: &str = "LATITUDE_VERSION_ID";
+pub(crate) const BASE_URL_ENV: &str = "LATITUDE_BASE_URL";
+
+/// FileConfig holds the client settings read from a config file such as `.latituderc`.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+pub(crate) struct FileConfig {
+    pub api_key: Option<String>,
+    pub project_id: Option<u64>,
+    pub version_id: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl FileConfig {
+    /// Reads a config file, parsed as JSON if it has a `.json` extension and as TOML otherwise.
+    pub(crate) fn load(path: &Path) -> Result<Self, Error> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| Error::ConfigError(format!("Failed to read {}: {}", path.display(), e)))?;
+
+        let is_json = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+
+        if is_json {
+            serde_json::from_str(&contents).map_err(|e| {
+                Error::ConfigError(format!("Invalid config file {}: {}", path.display(), e))
+            })
+        } else {
+            toml::from_str(&contents).map_err(|e| {
+                Error::ConfigError(format!("Invalid config file {}: {}", path.display(), e))
+            })
+        }
+    }
+
+    /// Overrides the values with the `LATITUDE_*` environment variables that are set.
+    pub(crate) fn with_env_overrides(mut self) -> Result<Self, Error> {
+        if let Ok(api_key) = env::var(API_KEY_ENV) {
+            self.api_key = Some(api_key);
+        }
+
+        if let Ok(project_id) = env::var(PROJECT_ID_ENV) {
+            let project_id = project_id
+                .parse()
+                .map_err(|_| Error::ConfigError(format!("{} must be a number", PROJECT_ID_ENV)))?;
+            self.project_id = Some(project_id);
+        }
+
+        if let Ok(version_id) = env::var(VERSION_ID_ENV) {
+            self.version_id = Some(version_id);
+        }
+
+        if let Ok(base_url) = env::var(BASE_URL_ENV) {
+            self.base_url = Some(base_url);
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_config(suffix: &str, contents: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::Builder::new()
+            .suffix(suffix)
+            .tempfile()
+            .expect("Failed to create config file");
+        file.write_all(contents.as_bytes())
+            .expect("Failed to write config file");
+        file
+    }
+
+    #[test]
+    fn test_load_toml_and_json() {
+        let expected = FileConfig {
+            api_key: Some("file_api_key".to_owned()),
+            project_id: Some(42),
+            version_id: None,
+            base_url: Some("https://custom.url/api".to_owned()),
+        };
+
+        let toml = write_config(
+            ".latituderc",
+            "api_key = \"file_api_key\"\nproject_id = 42\nbase_url = \"https://custom.url/api\"\n",
+        );
+        let json = write_config(
+            ".json",
+            r#"{"api_key":"file_api_key","project_id":42,"base_url":"https://custom.url/api"}"#,
+        );
+
+        assert_eq!(FileConfig::load(toml.path()).unwrap(), expected);
+        assert_eq!(FileConfig::load(json.path()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let result = FileConfig::load(Path::new("does-not-exist.latituderc"));
+
+        assert!(matches!(result, Err(Error::ConfigError(_))));
+    }
+}
@@ -1,14 +1,31 @@
-use reqwest;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use thiserror::Error;
 
+use crate::models::event::ToolCall;
+
 /// The main error type for the Latitude API client, encapsulating all possible error scenarios.
+///
+/// The enum is non-exhaustive, as some variants only exist with the `client` feature.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum Error {
     /// Error originating from the Latitude API with specific error codes.
     #[error("Latitude API error: {0:?}")]
     LatitudeError(LatitudeErrorCodes),
 
+    /// The request was rate limited, with the delay the API asked to wait before retrying,
+    /// taken from the `Retry-After` header when present.
+    ///
+    /// Rate-limited responses used to map to `LatitudeError(LatitudeErrorCodes::RateLimitError)`,
+    /// which is no longer returned; match this variant instead.
+    #[error("Rate limited, retry after {retry_after:?}")]
+    RateLimited { retry_after: Option<Duration> },
+
+    /// Error reported by the API in the body of a failed response, with its message and code.
+    #[error("API error response: {0:?}")]
+    ApiResponse(Box<ApiErrorJsonResponse>),
+
     /// Error encountered during document execution (Run) with specific error codes.
     #[error("Run error: {0:?}")]
     RunError(RunErrorCodes),
@@ -25,11 +42,16 @@ pub enum Error {
     #[error("Database reference error: {0:?}")]
     DatabaseError(DbErrorRef),
 
+    /// The run stopped to call tools, so it has no final text until their results are sent.
+    #[error("The run awaits the results of {} tool calls", .0.len())]
+    PendingToolCalls(Vec<ToolCall>),
+
     /// Error indicating an unexpected response format from the API.
     #[error("Unexpected response format: {0}")]
     ResponseFormatError(String),
 
     /// HTTP request-related error, mapped directly from `reqwest::Error`.
+    #[cfg(feature = "client")]
     #[error("HTTP error: {0}")]
     HttpError(#[from] reqwest::Error),
 
@@ -37,6 +59,10 @@ pub enum Error {
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
 
+    /// I/O error, e.g. while writing a streamed response, mapped directly from `std::io::Error`.
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+
     /// Configuration error, such as missing or invalid configuration values.
     #[error("Configuration error: {0}")]
     ConfigError(String),
@@ -44,14 +70,92 @@ pub enum Error {
     /// A catch-all error for miscellaneous cases.
     #[error("Other error: {0}")]
     Other(String),
+
+    /// An error with call-site context attached, such as the endpoint or path that failed.
+    #[error("{context}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Attaches call-site context to the error, keeping the error as its source.
+    ///
+    /// The resulting error displays the context, while walking `std::error::Error::source`
+    /// yields the original error and its own sources.
+    ///
+    /// # Arguments
+    /// * `context` - A description of what failed, e.g. which endpoint or path.
+    ///
+    /// # Example
+    /// ```
+    /// use latitude_sdk::error::Error;
+    /// use std::error::Error as _;
+    ///
+    /// let error = Error::Other("timed out".to_owned()).context("running Workers/EmotionAnalyzer");
+    ///
+    /// assert_eq!(error.to_string(), "running Workers/EmotionAnalyzer");
+    /// assert_eq!(error.source().unwrap().to_string(), "Other error: timed out");
+    /// ```
+    pub fn context(self, context: &str) -> Error {
+        Error::Context {
+            context: context.to_owned(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Maps the error to the HTTP status a service re-exposing Latitude results would answer.
+    ///
+    /// Errors reported by the API keep their meaning (e.g. rate limits map to 429 and
+    /// missing resources to 404), problems with the document or its parameters map to 400,
+    /// failures of the upstream API or the connection to it map to 502 (504 on timeouts),
+    /// and anything going wrong locally maps to 500.
+    ///
+    /// # Returns
+    /// The HTTP status code.
+    ///
+    /// # Example
+    /// ```
+    /// use latitude_sdk::error::{Error, LatitudeErrorCodes};
+    ///
+    /// assert_eq!(Error::LatitudeError(LatitudeErrorCodes::NotFoundError).http_status(), 404);
+    /// ```
+    pub fn http_status(&self) -> u16 {
+        match self {
+            Error::LatitudeError(code) => code.http_status(),
+            Error::RateLimited { .. } => 429,
+            Error::ApiResponse(response) => match &response.error_code {
+                ApiResponseCode::LatitudeError(code) => code.http_status(),
+                ApiResponseCode::RunError(code) => code.http_status(),
+                ApiResponseCode::ApiError(_) | ApiResponseCode::Other(_) => 502,
+            },
+            Error::RunError(code) => code.http_status(),
+            Error::ChainCompileError(_) => 400,
+            Error::ApiError(_) | Error::ResponseFormatError(_) => 502,
+            #[cfg(feature = "client")]
+            Error::HttpError(error) if error.is_timeout() => 504,
+            #[cfg(feature = "client")]
+            Error::HttpError(_) => 502,
+            Error::DatabaseError(_)
+            | Error::SerializationError(_)
+            | Error::IoError(_)
+            | Error::ConfigError(_)
+            | Error::PendingToolCalls(_)
+            | Error::Other(_) => 500,
+            Error::Context { source, .. } => source.http_status(),
+        }
+    }
 }
 
 /// Latitude API-specific error codes.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum LatitudeErrorCodes {
     /// An unexpected error occurred.
     UnexpectedError,
     /// The request was rate-limited.
+    #[deprecated(note = "rate-limited responses map to `Error::RateLimited`")]
     RateLimitError,
     /// The request was unauthorized.
     UnauthorizedError,
@@ -67,8 +171,24 @@ pub enum LatitudeErrorCodes {
     UnprocessableEntityError,
 }
 
+impl LatitudeErrorCodes {
+    fn http_status(&self) -> u16 {
+        match self {
+            LatitudeErrorCodes::UnexpectedError => 500,
+            #[allow(deprecated)]
+            LatitudeErrorCodes::RateLimitError => 429,
+            LatitudeErrorCodes::UnauthorizedError => 401,
+            LatitudeErrorCodes::ForbiddenError => 403,
+            LatitudeErrorCodes::BadRequestError => 400,
+            LatitudeErrorCodes::NotFoundError => 404,
+            LatitudeErrorCodes::ConflictError => 409,
+            LatitudeErrorCodes::UnprocessableEntityError => 422,
+        }
+    }
+}
+
 /// Error codes related to document execution (Run) within the Latitude API.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum RunErrorCodes {
     /// An unknown error occurred during document execution.
     Unknown,
@@ -98,8 +218,22 @@ pub enum RunErrorCodes {
     EvaluationRunResponseJsonFormat,
 }
 
+impl RunErrorCodes {
+    fn http_status(&self) -> u16 {
+        match self {
+            RunErrorCodes::DefaultProviderExceededQuota => 429,
+            RunErrorCodes::DefaultProviderInvalidModel
+            | RunErrorCodes::DocumentConfigError
+            | RunErrorCodes::MissingProvider
+            | RunErrorCodes::ChainCompileError
+            | RunErrorCodes::AIProviderConfigError => 400,
+            _ => 502,
+        }
+    }
+}
+
 /// General API error codes used by the Latitude API.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum ApiErrorCodes {
     /// An HTTP-related exception occurred.
     HTTPException,
@@ -117,7 +251,8 @@ pub struct RunErrorDetails {
 }
 
 /// Reference details for errors that involve a database entity.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DbErrorRef {
     /// UUID of the entity involved in the error.
     pub entity_uuid: String,
@@ -126,22 +261,25 @@ pub struct DbErrorRef {
 }
 
 /// General structure for handling API error responses in JSON format.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ApiErrorJsonResponse {
     /// Name of the error.
     pub name: String,
     /// Descriptive error message.
     pub message: String,
     /// Detailed information related to the error.
+    #[serde(default)]
     pub details: serde_json::Value,
     /// The specific error code for the response.
     pub error_code: ApiResponseCode,
     /// Optional reference to a database entity involved in the error.
+    #[serde(default)]
     pub db_error_ref: Option<DbErrorRef>,
 }
 
 /// Unified error code type that includes all possible error codes returned by the API.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ApiResponseCode {
     /// Error code for Latitude-specific issues.
@@ -150,4 +288,106 @@ pub enum ApiResponseCode {
     RunError(RunErrorCodes),
     /// General API error code.
     ApiError(ApiErrorCodes),
+    /// An error code this client doesn't know, kept as sent so the response isn't lost.
+    Other(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_context_preserves_source_chain() {
+        let serde_error = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        let serde_message = serde_error.to_string();
+
+        let error = Error::from(serde_error).context("parsing response of Workers/Summarizer");
+
+        let mut chain = vec![error.to_string()];
+        let mut source = error.source();
+        while let Some(error) = source {
+            chain.push(error.to_string());
+            source = error.source();
+        }
+
+        assert_eq!(
+            chain,
+            vec![
+                "parsing response of Workers/Summarizer".to_owned(),
+                format!("Serialization error: {}", serde_message),
+                serde_message,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_http_status_of_api_errors() {
+        let status = |code| Error::LatitudeError(code).http_status();
+
+        #[allow(deprecated)]
+        let rate_limit_error = LatitudeErrorCodes::RateLimitError;
+        assert_eq!(status(rate_limit_error), 429);
+        assert_eq!(status(LatitudeErrorCodes::UnauthorizedError), 401);
+        assert_eq!(status(LatitudeErrorCodes::ForbiddenError), 403);
+        assert_eq!(status(LatitudeErrorCodes::NotFoundError), 404);
+        assert_eq!(status(LatitudeErrorCodes::BadRequestError), 400);
+        assert_eq!(status(LatitudeErrorCodes::ConflictError), 409);
+        assert_eq!(status(LatitudeErrorCodes::UnprocessableEntityError), 422);
+        assert_eq!(status(LatitudeErrorCodes::UnexpectedError), 500);
+        assert_eq!(Error::RateLimited { retry_after: None }.http_status(), 429);
+        assert_eq!(
+            Error::ApiError(ApiErrorCodes::InternalServerError).http_status(),
+            502
+        );
+    }
+
+    #[test]
+    fn test_http_status_of_run_errors() {
+        let status = |code| Error::RunError(code).http_status();
+
+        assert_eq!(status(RunErrorCodes::DefaultProviderExceededQuota), 429);
+        assert_eq!(status(RunErrorCodes::DocumentConfigError), 400);
+        assert_eq!(status(RunErrorCodes::MissingProvider), 400);
+        assert_eq!(status(RunErrorCodes::AIRunError), 502);
+        assert_eq!(
+            Error::ChainCompileError(RunErrorDetails {
+                compile_code: "unclosed-tag".to_owned(),
+                message: "Unclosed tag".to_owned(),
+            })
+            .http_status(),
+            400
+        );
+    }
+
+    #[test]
+    fn test_http_status_of_local_errors() {
+        let serde_error = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+
+        assert_eq!(Error::ConfigError("missing".to_owned()).http_status(), 500);
+        assert_eq!(Error::from(serde_error).http_status(), 500);
+        assert_eq!(Error::Other("unknown".to_owned()).http_status(), 500);
+        assert_eq!(
+            Error::ResponseFormatError("unexpected".to_owned()).http_status(),
+            502
+        );
+        assert_eq!(
+            Error::LatitudeError(LatitudeErrorCodes::NotFoundError)
+                .context("getting Workers/Summarizer")
+                .http_status(),
+            404
+        );
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn test_http_status_of_transport_errors() {
+        let error = reqwest::Client::new()
+            .get("http://127.0.0.1:1")
+            .send()
+            .await
+            .unwrap_err();
+
+        assert_eq!(Error::from(error).http_status(), 502);
+    }
 }
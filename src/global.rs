@@ -0,0 +1,68 @@
+use std::sync::OnceLock;
+
+use crate::{error::Error, Client, ClientBuilder};
+
+static CLIENT: OnceLock<Client> = OnceLock::new();
+
+/// Initializes the global default `Client`.
+///
+/// This is an opt-in convenience for small apps and scripts that would rather not thread
+/// a `Client` through their code; explicitly created clients are unaffected.
+///
+/// # Arguments
+/// * `builder` - The configured `ClientBuilder` of the global client.
+///
+/// # Returns
+/// `Ok(())` if the global client was initialized, or `Error::ConfigError` if the builder's
+/// configuration is invalid or the global client already was initialized.
+///
+/// # Example
+/// ```
+/// use latitude_sdk::Client;
+///
+/// latitude_sdk::init(Client::builder("your_api_key".into()).project_id(123))
+///     .expect("Global client already initialized");
+///
+/// let client = latitude_sdk::client();
+/// ```
+pub fn init(builder: ClientBuilder) -> Result<(), Error> {
+    let client = builder.build()?;
+
+    CLIENT
+        .set(client)
+        .map_err(|_| Error::ConfigError("Global client is already initialized".to_owned()))
+}
+
+/// Returns the global default `Client`.
+///
+/// # Panics
+/// Panics if the global client hasn't been initialized with `init`.
+pub fn client() -> &'static Client {
+    CLIENT
+        .get()
+        .expect("Global client is not initialized, call latitude_sdk::init first")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_global_client() {
+        assert!(matches!(
+            init(Client::builder("global_api_key\n".into())),
+            Err(Error::ConfigError(_))
+        ));
+        assert!(CLIENT.get().is_none());
+
+        init(Client::builder("global_api_key".into()).project_id(42))
+            .expect("Failed to initialize global client");
+
+        assert_eq!(client().api_key, "global_api_key");
+        assert!(matches!(
+            init(Client::builder("other_api_key".into())),
+            Err(Error::ConfigError(_))
+        ));
+        assert_eq!(client().api_key, "global_api_key");
+    }
+}
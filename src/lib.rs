@@ -14,6 +14,14 @@
 //! - **Stream Responses**: Optionally receive responses as a real-time data stream.
 //! - **Simple API Integration**: API key authentication and project/version management.
 //!
+//! ## Feature Flags
+//! - `client` (default): The HTTP client. Without it (`default-features = false`), only the
+//!   `models` and `error` types are built, without the HTTP and async runtime dependencies.
+//! - `pricing`: Built-in list prices of common models via `ModelPricing::for_model`.
+//! - `schema-validation`: Validates run parameters against a JSON schema before sending them.
+//! - `test-util`: Helpers for mocking the API in tests.
+//! - `tokenize`: Local token counting via `Client::estimate_tokens`.
+//!
 //! ## Installation
 //!
 //! Add this crate to your `Cargo.toml` file:
@@ -27,41 +35,133 @@
 //! To use the Latitude API client, create an instance of `Client` with your API key, set the project ID, and run a document.
 //!
 //! ```rust
+//! # #[cfg(feature = "client")] {
 //! use latitude_sdk::Client;
 //!
 //! let client = Client::builder("your_api_key".into())
 //!     .project_id(123)
 //!     .version_id("version-uuid".to_string())
 //!     .base_url("https://custom.url/api".to_string())
-//!     .build();
+//!     .build()?;
+//! # }
+//! # Ok::<(), latitude_sdk::error::Error>(())
 //! ```
 
-use async_sse::decode;
-use error::{Error, LatitudeErrorCodes};
+#[cfg(feature = "client")]
+use error::{ApiErrorCodes, ApiErrorJsonResponse, Error, LatitudeErrorCodes, RunErrorCodes};
+#[cfg(feature = "client")]
+use futures_util::{
+    future::{self, Either},
+    Stream,
+};
+#[cfg(feature = "client")]
 use models::{
+    accumulator::StreamAccumulator,
     chat::Chat,
+    commit::Commit,
     document::{Document, RunDocument, RunResponse},
-    evaluate::{Evaluation, EvaluationResponse},
-    event::Event,
+    evaluate::{Evaluation, EvaluationResponse, EvaluationResult},
+    event::{Event, LatitudeEvent, LatitudeEventType, ProviderEventType, ToolCallEvent, Usage},
     log::{Log, LogResponse},
+    message::{Content, Message},
     options::Options,
-    response::Response,
+    response::{self, EventStream, Response, RunCanceller, SlowConsumerPolicy, Timing},
 };
+#[cfg(feature = "client")]
 use reqwest::{
-    header::{HeaderMap, HeaderValue},
-    Client as ReqwestClient, StatusCode,
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, RETRY_AFTER},
+    redirect::Policy,
+    Client as ReqwestClient, Method, RequestBuilder, StatusCode,
+};
+#[cfg(feature = "client")]
+use serde::{de::DeserializeOwned, Serialize};
+#[cfg(feature = "client")]
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    io::Write,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+#[cfg(feature = "client")]
+use stream::EventNames;
+#[cfg(feature = "client")]
+use tokio::{
+    sync::{mpsc, oneshot, watch},
+    task::JoinSet,
 };
-use serde::Serialize;
-use tokio::{io::BufReader, sync::mpsc};
+#[cfg(feature = "client")]
 use tokio_stream::StreamExt;
-use tokio_util::{compat::TokioAsyncReadCompatExt, io::StreamReader};
-use tracing::error;
+#[cfg(feature = "client")]
+use tokio_util::{compat::TokioAsyncReadCompatExt, io::StreamReader, sync::CancellationToken};
+#[cfg(feature = "client")]
+use tracing::{debug, error};
 
+#[cfg(feature = "client")]
+mod config;
 pub mod error;
+#[cfg(feature = "client")]
+mod global;
 pub mod models;
-
+#[cfg(feature = "client")]
+mod request;
+#[cfg(feature = "client")]
+mod scoped;
+#[cfg(feature = "client")]
+mod stream;
+#[cfg(all(feature = "client", any(test, feature = "test-util")))]
+pub mod test_util;
+#[cfg(feature = "tokenize")]
+mod tokenize;
+
+#[cfg(feature = "client")]
+pub use global::{client, init};
+
+#[cfg(feature = "client")]
+pub use request::RunRequestBuilder;
+#[cfg(feature = "client")]
+pub use scoped::ScopedClient;
+#[cfg(feature = "client")]
+pub use stream::{decode_events, decode_stream};
+
+#[cfg(feature = "client")]
 static BASE_URL: &str = "https://gateway.latitude.so/api/v2";
-static APP_USER_AGENT: &str = env!("CARGO_PKG_NAME");
+#[cfg(feature = "client")]
+static SDK_VERSION: &str = env!("CARGO_PKG_VERSION");
+#[cfg(feature = "client")]
+static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+#[cfg(feature = "client")]
+static MAX_REDIRECTS: usize = 10;
+#[cfg(feature = "client")]
+static EVALUATION_POLL_INITIAL_DELAY: Duration = Duration::from_millis(250);
+#[cfg(feature = "client")]
+static EVALUATION_POLL_MAX_DELAY: Duration = Duration::from_secs(5);
+#[cfg(feature = "client")]
+static RETRY_DELAY: Duration = Duration::from_millis(250);
+#[cfg(feature = "client")]
+static RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+#[cfg(feature = "client")]
+static LIVE_VERSION: &str = "live";
+#[cfg(feature = "client")]
+static STREAM_BUFFER: usize = 100;
+#[cfg(feature = "client")]
+static STREAM_ID: &str = "x-stream-id";
+#[cfg(feature = "client")]
+static REQUEST_ID: &str = "x-request-id";
+#[cfg(feature = "client")]
+static WORKSPACE_ID: &str = "x-workspace-id";
+
+/// Callback invoked with the reported token usage whenever a streamed event carries usage.
+#[cfg(feature = "client")]
+pub type StreamProgressCallback = Arc<dyn Fn(&Usage) + Send + Sync>;
+
+/// Callback invoked with the timing breakdown of every successful request.
+#[cfg(feature = "client")]
+pub type MetricsCallback = Arc<dyn Fn(&Timing) + Send + Sync>;
 
 /// The `Client` for interacting with the Latitude API.
 ///
@@ -78,8 +178,10 @@ static APP_USER_AGENT: &str = env!("CARGO_PKG_NAME");
 ///     .project_id(123)
 ///     .version_id("version-uuid".to_string())
 ///     .base_url("https://custom.url/api".to_string())
-///     .build();
+///     .build()?;
+/// # Ok::<(), latitude_sdk::error::Error>(())
 /// ```
+#[cfg(feature = "client")]
 #[derive(Clone)]
 pub struct Client {
     /// The API key for authentication.
@@ -92,8 +194,76 @@ pub struct Client {
     client: ReqwestClient,
     /// The base URL for API requests.
     base_url: String,
+    /// Callback invoked on each usage-bearing event of a streamed response.
+    on_stream_progress: Option<StreamProgressCallback>,
+    /// Callback invoked with the timing breakdown of every successful request.
+    on_metrics: Option<MetricsCallback>,
+    /// Whether successful responses carrying an `error` body are treated as errors.
+    strict_error_bodies: bool,
+    /// Whether streamed events keep their raw `data:` payload.
+    capture_raw_events: bool,
+    /// The SSE event names of streamed Latitude and provider events.
+    event_names: EventNames,
+    /// Whether message content is replaced by its length in tracing output.
+    redact_content: bool,
+    /// How often a rate-limited or failed request is retried, unless overridden per call.
+    max_retries: u32,
+    /// The delay before the first retry, doubled on every further retry.
+    retry_base_delay: Duration,
+    /// The version used when neither the call nor the client sets one.
+    default_version_fallback: String,
+    /// What happens when the consumer of a stream falls behind.
+    slow_consumer_policy: SlowConsumerPolicy,
+    /// How many events of a stream are buffered until its consumer receives them.
+    stream_buffer: usize,
+    /// How long a stream may go without receiving a frame before it's closed.
+    stream_idle_timeout: Option<Duration>,
+    /// The largest request body sent, checked before the request is sent.
+    max_request_bytes: Option<usize>,
+    /// The workspace requests are scoped to, unless overridden per call.
+    workspace_id: Option<String>,
+    /// The background tasks of in-flight streams, shared by clones of the client.
+    stream_tasks: Arc<Mutex<StreamTasks>>,
+    /// Whether `shutdown` was called, after which no new requests are sent.
+    shut_down: Arc<AtomicBool>,
+}
+
+/// When a request was first sent, and how long until its response headers were received.
+#[cfg(feature = "client")]
+#[derive(Debug, Clone, Copy)]
+struct RequestTiming {
+    started: Instant,
+    request_sent: Duration,
+}
+
+#[cfg(feature = "client")]
+impl RequestTiming {
+    /// Returns the timing of the request, completed now.
+    fn finish(&self, first_event: Option<Instant>) -> Timing {
+        Timing {
+            request_sent: self.request_sent,
+            first_event: first_event.map(|instant| instant - self.started),
+            completed: self.started.elapsed(),
+        }
+    }
+}
+
+/// The background tasks of in-flight streams.
+///
+/// Unlike a bare `JoinSet`, dropping it detaches the tasks rather than aborting them, so
+/// streams outlive the last clone of the client they were started from.
+#[cfg(feature = "client")]
+#[derive(Debug, Default)]
+struct StreamTasks(JoinSet<()>);
+
+#[cfg(feature = "client")]
+impl Drop for StreamTasks {
+    fn drop(&mut self) {
+        self.0.detach_all();
+    }
 }
 
+#[cfg(feature = "client")]
 impl Client {
     /// Creates a new `Client` with the provided API key.
     ///
@@ -109,32 +279,88 @@ impl Client {
     ///
     /// let client = Client::new("your_api_key".into(), None, None, None);
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the API key contains characters invalid in an HTTP header, such as a
+    /// newline; use `try_new` to handle this as an error.
     pub fn new(
         api_key: String,
         project_id: Option<u64>,
         version_id: Option<String>,
         base_url: Option<String>,
     ) -> Self {
-        let mut headers = HeaderMap::new();
-        let api_key_value =
-            HeaderValue::from_str(&format!("Bearer {}", api_key)).expect("Invalid API key");
-        headers.insert("Authorization", api_key_value);
+        Self::try_new(api_key, project_id, version_id, base_url).expect("Invalid API key")
+    }
 
-        let client = ReqwestClient::builder()
-            .default_headers(headers)
-            .user_agent(APP_USER_AGENT)
-            .build()
-            .expect("Failed to create HTTP client");
+    /// Creates a new `Client` with the provided API key, checking that the key is valid.
+    ///
+    /// # Arguments
+    /// * `api_key` - The API key for authenticating with the Latitude API.
+    /// * `project_id` - The default project ID used in requests.
+    /// * `version_id` - The default version UUID used in requests.
+    /// * `base_url` - The base URL for API requests. Defaults to the Latitude API endpoint.
+    ///
+    /// # Returns
+    /// * The `Client`, or `Error::ConfigError` if the API key contains characters invalid
+    ///   in an HTTP header.
+    ///
+    /// # Examples
+    /// ```
+    /// use latitude_sdk::{error::Error, Client};
+    ///
+    /// let result = Client::try_new("your_api_key\n".into(), None, None, None);
+    /// assert!(matches!(result, Err(Error::ConfigError(_))));
+    /// ```
+    pub fn try_new(
+        api_key: String,
+        project_id: Option<u64>,
+        version_id: Option<String>,
+        base_url: Option<String>,
+    ) -> Result<Self, Error> {
+        Self::with_http_client(
+            api_key,
+            project_id,
+            version_id,
+            base_url,
+            Self::http_client(Self::same_origin_redirects(), HeaderMap::new()),
+        )
+    }
 
+    /// Creates a new `Client` sending its requests with the given HTTP client.
+    fn with_http_client(
+        api_key: String,
+        project_id: Option<u64>,
+        version_id: Option<String>,
+        base_url: Option<String>,
+        client: ReqwestClient,
+    ) -> Result<Self, Error> {
+        Self::auth_header(&api_key)?;
         let base_url = base_url.unwrap_or_else(|| BASE_URL.into());
 
-        Self {
+        Ok(Self {
             api_key,
             project_id,
             version_id,
             client,
             base_url,
-        }
+            on_stream_progress: None,
+            on_metrics: None,
+            strict_error_bodies: false,
+            capture_raw_events: false,
+            event_names: EventNames::default(),
+            redact_content: false,
+            max_retries: 0,
+            retry_base_delay: RETRY_DELAY,
+            default_version_fallback: LIVE_VERSION.into(),
+            slow_consumer_policy: SlowConsumerPolicy::default(),
+            stream_buffer: STREAM_BUFFER,
+            stream_idle_timeout: None,
+            max_request_bytes: None,
+            workspace_id: None,
+            stream_tasks: Arc::default(),
+            shut_down: Arc::default(),
+        })
     }
 
     /// Creates a new `ClientBuilder` with the required API key.
@@ -160,7 +386,394 @@ impl Client {
             project_id: None,
             version_id: None,
             base_url: BASE_URL.into(),
+            on_stream_progress: None,
+            on_metrics: None,
+            strict_error_bodies: false,
+            capture_raw_events: false,
+            event_names: EventNames::default(),
+            redact_content: false,
+            max_retries: 0,
+            retry_base_delay: RETRY_DELAY,
+            default_version_fallback: LIVE_VERSION.into(),
+            slow_consumer_policy: SlowConsumerPolicy::default(),
+            stream_buffer: STREAM_BUFFER,
+            stream_idle_timeout: None,
+            max_request_bytes: None,
+            workspace_id: None,
+            redirect_policy: None,
+            http_client: None,
+            headers: HeaderMap::new(),
+            invalid_header: None,
+        }
+    }
+
+    /// Creates a new `Client` from a config file, such as a `.latituderc`.
+    ///
+    /// The file may set `api_key`, `project_id`, `version_id` and `base_url`, and is
+    /// parsed as JSON if it has a `.json` extension and as TOML otherwise. The
+    /// `LATITUDE_API_KEY`, `LATITUDE_PROJECT_ID`, `LATITUDE_VERSION_ID` and
+    /// `LATITUDE_BASE_URL` environment variables override the values of the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the config file.
+    ///
+    /// # Returns
+    ///
+    /// The configured `Client`, or `Error::ConfigError` if the file can't be read or
+    /// parsed, or no API key is configured.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use latitude_sdk::Client;
+    ///
+    /// let client = Client::from_config_file(".latituderc").expect("Invalid config");
+    /// ```
+    pub fn from_config_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_file_config(config::FileConfig::load(path.as_ref())?.with_env_overrides()?)
+    }
+
+    /// Creates a new `Client` from the environment.
+    ///
+    /// Reads the API key from `LATITUDE_API_KEY`, and the optional project, version and
+    /// base URL from `LATITUDE_PROJECT_ID`, `LATITUDE_VERSION_ID` and `LATITUDE_BASE_URL`.
+    ///
+    /// # Returns
+    ///
+    /// The configured `Client`, or `Error::ConfigError` if `LATITUDE_API_KEY` isn't set
+    /// or `LATITUDE_PROJECT_ID` isn't a number.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use latitude_sdk::Client;
+    ///
+    /// let client = Client::from_env().expect("LATITUDE_API_KEY is required");
+    /// ```
+    pub fn from_env() -> Result<Self, Error> {
+        Self::from_file_config(config::FileConfig::default().with_env_overrides()?)
+    }
+
+    fn from_file_config(config: config::FileConfig) -> Result<Self, Error> {
+        let api_key = config
+            .api_key
+            .ok_or_else(|| Error::ConfigError("API key is required".to_owned()))?;
+
+        Self::try_new(
+            api_key,
+            config.project_id,
+            config.version_id,
+            config.base_url,
+        )
+    }
+
+    /// Builds the HTTP client sending the User-Agent with every request.
+    ///
+    /// The API key isn't part of the HTTP client but is added to each request, so clients
+    /// with different keys can share the connection pool.
+    fn http_client(redirect_policy: Policy, headers: HeaderMap) -> ReqwestClient {
+        ReqwestClient::builder()
+            .user_agent(APP_USER_AGENT)
+            .default_headers(headers)
+            .redirect(redirect_policy)
+            .build()
+            .expect("Failed to create HTTP client")
+    }
+
+    /// Builds the `Authorization` header value for an API key.
+    fn auth_header(api_key: &str) -> Result<HeaderValue, Error> {
+        let mut value = HeaderValue::from_str(&format!("Bearer {}", api_key))
+            .map_err(|_| Error::ConfigError("Invalid API key".to_owned()))?;
+        value.set_sensitive(true);
+        Ok(value)
+    }
+
+    /// Serializes the JSON body of a request, checking it against `max_request_bytes`.
+    fn json_body(&self, body: &impl Serialize) -> Result<Vec<u8>, Error> {
+        let body = serde_json::to_vec(body)?;
+
+        match self.max_request_bytes {
+            Some(max_bytes) if body.len() > max_bytes => Err(Error::ConfigError(format!(
+                "The request body is about {}, over the limit of {}",
+                Self::approximate_size(body.len()),
+                Self::approximate_size(max_bytes)
+            ))),
+            _ => Ok(body),
+        }
+    }
+
+    /// Formats a size in bytes for humans, e.g. `1.5 MB`.
+    fn approximate_size(bytes: usize) -> String {
+        match bytes {
+            bytes if bytes >= 1_000_000 => format!("{:.1} MB", bytes as f64 / 1e6),
+            bytes if bytes >= 1_000 => format!("{:.1} KB", bytes as f64 / 1e3),
+            bytes => format!("{} bytes", bytes),
+        }
+    }
+
+    /// Starts a request to the API, authenticated with the client's API key.
+    fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        self.client.request(method, url).bearer_auth(&self.api_key)
+    }
+
+    /// Returns the version used when neither a call's options nor the client set one.
+    ///
+    /// This is `"live"` unless configured with `ClientBuilder::default_version_fallback`.
+    pub fn default_version_fallback(&self) -> &str {
+        &self.default_version_fallback
+    }
+
+    /// Resolves the version of a call: its options' version, else the client's
+    /// `version_id`, else the default version fallback.
+    fn resolve_version_id(&self, options: Option<&Options>) -> String {
+        options
+            .and_then(|opts| opts.version_id.clone())
+            .or(self.version_id.clone())
+            .unwrap_or_else(|| self.default_version_fallback.clone())
+    }
+
+    /// Sends a request, retrying it while it's rate limited or fails with a server error.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request to send.
+    /// * `options` - The call's options, whose `max_retries` overrides the client default.
+    async fn send(
+        &self,
+        request: RequestBuilder,
+        options: Option<&Options>,
+    ) -> Result<reqwest::Response, Error> {
+        if self.shut_down.load(Ordering::Relaxed) {
+            return Err(Error::ConfigError("The client is shut down".to_owned()));
+        }
+
+        let max_retries = options
+            .and_then(|opts| opts.max_retries)
+            .unwrap_or(self.max_retries);
+        let request = match options.and_then(|opts| opts.timeout) {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        };
+        let workspace_id = options
+            .and_then(|opts| opts.workspace_id.as_ref())
+            .or(self.workspace_id.as_ref());
+        let request = match workspace_id {
+            Some(workspace_id) => request.header(WORKSPACE_ID, workspace_id),
+            None => request,
+        };
+
+        let started = Instant::now();
+        let mut retries = 0;
+        loop {
+            let attempt = request
+                .try_clone()
+                .ok_or_else(|| Error::Other("The request can't be retried".to_owned()))?;
+            let mut response = attempt.send().await?;
+
+            let status = response.status();
+            let transient = status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+            let retry_after = Self::retry_after(response.headers());
+            // A server asking to wait longer than any backoff would isn't waited for; the
+            // caller gets its `Retry-After` with the error instead.
+            let too_late = retry_after.is_some_and(|delay| delay > RETRY_MAX_DELAY);
+            if !transient || too_late || retries >= max_retries {
+                response.extensions_mut().insert(RequestTiming {
+                    started,
+                    request_sent: started.elapsed(),
+                });
+                return Ok(response);
+            }
+
+            let delay = retry_after.unwrap_or_else(|| self.backoff(retries));
+            retries += 1;
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Reads a JSON response body, reporting the timing of the request once it's received.
+    async fn json<T: DeserializeOwned>(&self, response: reqwest::Response) -> Result<T, Error> {
+        let timing = response.extensions().get::<RequestTiming>().copied();
+        let body = response.json::<T>().await?;
+
+        if let (Some(timing), Some(callback)) = (timing, &self.on_metrics) {
+            callback(&timing.finish(None));
+        }
+
+        Ok(body)
+    }
+
+    /// Parses the JSON body of a non-streamed run or chat, along with its request ID.
+    async fn run_response(&self, response: reqwest::Response) -> Result<RunResponse, Error> {
+        let request_id = response
+            .headers()
+            .get(REQUEST_ID)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+
+        let mut response = if self.strict_error_bodies {
+            let body = self.json::<serde_json::Value>(response).await?;
+            Self::check_error_body(&body)?;
+            serde_json::from_value::<RunResponse>(body)?
+        } else {
+            self.json::<RunResponse>(response).await?
+        };
+        response.request_id = request_id;
+
+        Ok(response)
+    }
+
+    /// Returns the delay before retrying a request for the given time, with jitter.
+    ///
+    /// The delay doubles on every retry, and is drawn between half of it and all of it.
+    fn backoff(&self, retries: u32) -> Duration {
+        let delay = self
+            .retry_base_delay
+            .saturating_mul(1 << retries.min(16))
+            .min(RETRY_MAX_DELAY);
+        let random = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+
+        delay / 2 + (delay / 2).mul_f64(random)
+    }
+
+    /// Parses the delay of a `Retry-After` header, given in seconds or as an HTTP date.
+    ///
+    /// A date in the past yields a zero delay.
+    fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+        let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+        if let Ok(seconds) = value.parse() {
+            return Some(Duration::from_secs(seconds));
         }
+
+        let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+        Some(
+            (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+                .to_std()
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Returns a copy of this client authenticating with a different API key.
+    ///
+    /// The copy shares the connection pool and every other setting of this client, which
+    /// makes it suitable for rotating keys in long-running services.
+    ///
+    /// # Arguments
+    ///
+    /// * `new_key` - The API key the new client authenticates with.
+    ///
+    /// # Returns
+    ///
+    /// The new `Client`, or `Error::ConfigError` if the key isn't a valid header value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    ///
+    /// let client = Client::builder("old_api_key".into()).project_id(123).build()?;
+    /// let rotated = client.clone_with_key("new_api_key".into()).expect("Invalid API key");
+    ///
+    /// assert_eq!(rotated.api_key, "new_api_key");
+    /// # Ok::<(), latitude_sdk::error::Error>(())
+    /// ```
+    pub fn clone_with_key(&self, new_key: String) -> Result<Client, Error> {
+        Self::auth_header(&new_key)?;
+
+        Ok(Client {
+            api_key: new_key,
+            ..self.clone()
+        })
+    }
+
+    /// The default redirect policy, which only follows redirects within the same origin.
+    ///
+    /// A cross-origin redirect isn't followed, and its response is returned as is.
+    fn same_origin_redirects() -> Policy {
+        Policy::custom(|attempt| {
+            let same_origin = attempt
+                .previous()
+                .last()
+                .is_some_and(|previous| previous.origin() == attempt.url().origin());
+
+            if !same_origin {
+                attempt.stop()
+            } else if attempt.previous().len() > MAX_REDIRECTS {
+                attempt.error("too many redirects")
+            } else {
+                attempt.follow()
+            }
+        })
+    }
+
+    /// Returns the version of this SDK.
+    ///
+    /// The version is also sent in the `User-Agent` header of every request, which helps
+    /// with support and compatibility checks.
+    ///
+    /// # Examples
+    /// ```
+    /// use latitude_sdk::Client;
+    ///
+    /// println!("latitude-sdk {}", Client::sdk_version());
+    /// ```
+    pub fn sdk_version() -> &'static str {
+        SDK_VERSION
+    }
+
+    /// Creates a `ScopedClient` view that uses the given options as its defaults.
+    ///
+    /// The scoped client shares this client's connection pool and configuration, so
+    /// it's a cheap way to give individual tasks different project/version defaults.
+    ///
+    /// # Arguments
+    /// * `options` - The default options for calls made through the scoped client.
+    ///
+    /// # Examples
+    /// ```
+    /// use latitude_sdk::{Client, models::options::Options};
+    ///
+    /// let client = Client::builder("your_api_key".into()).build()?;
+    /// let scoped = client.with_options(Options::new(None, Some(123)));
+    /// # Ok::<(), latitude_sdk::error::Error>(())
+    /// ```
+    pub fn with_options(&self, options: Options) -> ScopedClient<'_> {
+        ScopedClient::new(self, options)
+    }
+
+    /// Starts a fluent request running the document at `path`.
+    ///
+    /// This is a shorthand for building a `RunDocument` and passing it to `run`.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the document to run.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use latitude_sdk::Client;
+    /// use tokio_stream::StreamExt;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), latitude_sdk::error::Error> {
+    ///     let client = Client::builder("your_api_key".into()).project_id(123).build()?;
+    ///
+    ///     let mut events = client
+    ///         .prompt("Workers/Storyteller")
+    ///         .param("topic", "dragons")
+    ///         .send_stream()
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     while let Some(event) = events.next().await {
+    ///         println!("{:?}", event);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn prompt(&self, path: &str) -> RunRequestBuilder<'_> {
+        RunRequestBuilder::new(self, path)
     }
 
     /// Runs a document with the specified path and user-defined parameters, with an option for streaming responses.
@@ -185,29 +798,29 @@ impl Client {
     /// }
     ///
     /// #[tokio::main]
-    /// async fn main() {
+    /// async fn main() -> Result<(), latitude_sdk::error::Error> {
     /// let client = Client::builder("your_api_key".into())
     ///     .project_id(123)
     ///     .version_id("version-uuid".to_string())
     ///     .base_url("https://custom.url/api".to_string())
-    ///     .build();
+    ///     .build()?;
     ///
     ///     let params = Params {
     ///         user_message: "Hello, world!".to_owned(),
     ///     };
     ///
-    ///     let document = RunDocument {
-    ///         path: "Workers/EmotionAnalyzer".to_owned(),
-    ///         parameters: Some(params),
-    ///         stream: false,
-    ///         options: None
-    ///     };
+    ///     let document = RunDocument::builder()
+    ///         .path("Workers/EmotionAnalyzer".to_owned())
+    ///         .parameters(params)
+    ///         .build();
     ///
     ///     match client.run(document).await {
     ///         Ok(Response::Json(response)) => println!("JSON Response: {:?}", response),
     ///         _ => println!("Received a streaming response"),
     ///         Err(e) => eprintln!("Error: {:?}", e),
     ///     }
+    ///
+    ///     Ok(())
     /// }
     /// ```
     ///
@@ -225,37 +838,39 @@ impl Client {
     /// }
     ///
     /// #[tokio::main]
-    /// async fn main() {
+    /// async fn main() -> Result<(), latitude_sdk::error::Error> {
     /// let client = Client::builder("your_api_key".into())
     ///     .project_id(123)
     ///     .version_id("version-uuid".to_string())
     ///     .base_url("https://custom.url/api".to_string())
-    ///     .build();
+    ///     .build()?;
     ///
     ///     let params = Params {
     ///         user_message: "Hello, world!".to_owned(),
     ///     };
     ///
-    ///     let document = RunDocument {
-    ///         path: "Workers/EmotionAnalyzer".to_owned(),
-    ///         parameters: Some(params),
-    ///         stream: true,
-    ///         options: None
-    ///     };
+    ///     let document = RunDocument::builder()
+    ///         .path("Workers/EmotionAnalyzer".to_owned())
+    ///         .parameters(params)
+    ///         .stream()
+    ///         .build();
     ///
     ///     match client.run(document).await {
     ///         Ok(Response::Stream(mut event_stream)) => {
-    ///             while let Some(event) = event_stream.recv().await {
+    ///             while let Some(event) = event_stream.next().await {
     ///                 match event {
-    ///                     Event::LatitudeEvent(data) => println!("Latitude Event: {:?}", data),
-    ///                     Event::ProviderEvent(data) => println!("Provider Event: {:?}", data),
-    ///                     Event::UnknownEvent => println!("Unknown Event"),
+    ///                     Ok(Event::LatitudeEvent(data)) => println!("Latitude Event: {:?}", data),
+    ///                     Ok(Event::ProviderEvent(data)) => println!("Provider Event: {:?}", data),
+    ///                     Ok(Event::UnknownEvent) => println!("Unknown Event"),
+    ///                     Err(e) => eprintln!("Stream error: {:?}", e),
     ///                 }
     ///             }
     ///         },
     ///        _ => println!("Received a JSON response"),
     ///         Err(e) => eprintln!("Error: {:?}", e),
     ///     }
+    ///
+    ///     Ok(())
     /// }
     /// ```
     pub async fn run<T>(&self, document: RunDocument<T>) -> Result<Response, Error>
@@ -269,404 +884,4331 @@ impl Client {
             .or(self.project_id)
             .ok_or_else(|| Error::ConfigError("Project ID is required".to_owned()))?;
 
-        let version_id = document
-            .options
-            .as_ref()
-            .and_then(|opts| opts.version_id.clone())
-            .or(self.version_id.clone())
-            .unwrap_or_else(|| "live".to_string());
+        let version_id = self.resolve_version_id(document.options.as_ref());
+
+        if document.stream {
+            Self::runtime_handle()?;
+        }
+
+        debug!(
+            path = %document.path,
+            stream = document.stream,
+            parameters = %self.loggable(
+                &serde_json::to_string(&document.parameters).unwrap_or_default()
+            ),
+            "Running document"
+        );
 
         let url = format!(
             "{}/projects/{}/versions/{}/documents/run",
             self.base_url, project_id, version_id
         );
 
-        let response = self.client.post(&url).json(&document).send().await?;
+        let request = self
+            .request(Method::POST, &url)
+            .query(&document.query_params)
+            .header(CONTENT_TYPE, "application/json")
+            .body(self.json_body(&document)?);
+        let response = self.send(request, document.options.as_ref()).await?;
 
-        Self::check_status(response.status())?;
+        let response = Self::check_response(response).await?;
 
         if document.stream {
-            let stream = response.bytes_stream();
-            let (sender, receiver) = mpsc::channel(100);
-
-            tokio::spawn(async move {
-                let reader = StreamReader::new(stream.map(|result| {
-                    result.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
-                }));
-                let buffered_reader = BufReader::new(reader.compat().into_inner());
-                let mut decoder = decode(buffered_reader.compat());
+            return self.stream_events(response).await;
+        }
 
-                while let Some(event) = decoder.next().await {
-                    match event {
-                        Ok(async_sse::Event::Message(message)) => {
-                            let data = message.data();
-                            let parsed_event = match message.name().as_str() {
-                                "latitude-event" => serde_json::from_slice(data)
-                                    .map(Event::LatitudeEvent)
-                                    .map_err(Error::from),
-                                "provider-event" => serde_json::from_slice(data)
-                                    .map(Event::ProviderEvent)
-                                    .map_err(Error::from),
-                                _ => Ok(Event::UnknownEvent),
-                            };
-
-                            if let Ok(event) = parsed_event {
-                                if sender.send(event).await.is_err() {
-                                    break;
-                                }
-                            }
-                        }
-                        Ok(async_sse::Event::Retry(_)) => {}
-                        Err(e) => {
-                            eprintln!("Streaming error: {:?}", e);
-                            break;
-                        }
-                    }
-                }
-            });
+        let response = self.run_response(response).await?;
 
-            return Ok(Response::Stream(receiver));
+        #[cfg(feature = "schema-validation")]
+        if let Some(schema) = &document.output_schema {
+            response.validate_schema(schema)?;
         }
 
-        response
-            .json::<RunResponse>()
-            .await
-            .map(Response::Json)
-            .map_err(Error::from)
+        Ok(Response::Json(response))
     }
 
-    pub async fn chat(&self, chat: Chat) -> Result<Response, Error> {
-        if !chat.stream {
-            unimplemented!()
+    /// Runs a document and returns the text of its response.
+    ///
+    /// The document is always run without streaming.
+    ///
+    /// # Arguments
+    /// * `document` - The `RunDocument` to run.
+    ///
+    /// # Returns
+    /// * The text of the response, or `Error::PendingToolCalls` if the run stopped to call
+    ///   tools rather than answering; their results are sent with `chat`.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use latitude_sdk::{Client, error::Error, models::document::RunDocument};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::builder("your_api_key".into()).project_id(123).build()?;
+    ///
+    ///     let document = RunDocument::<()>::builder()
+    ///         .path("Agents/Assistant".to_owned())
+    ///         .build();
+    ///
+    ///     match client.run_text(document).await {
+    ///         Ok(text) => println!("{}", text),
+    ///         Err(Error::PendingToolCalls(tool_calls)) => println!("Calling {:?}", tool_calls),
+    ///         Err(e) => eprintln!("Error: {:?}", e),
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_text<T>(&self, mut document: RunDocument<T>) -> Result<String, Error>
+    where
+        T: Serialize + std::fmt::Debug,
+    {
+        document.stream = false;
+
+        let response = match self.run(document).await? {
+            Response::Json(response) => response.response,
+            Response::Stream(_) => {
+                return Err(Error::ResponseFormatError(
+                    "Expected a JSON response".to_owned(),
+                ))
+            }
+        };
+
+        if response.awaits_tool_results() {
+            return Err(Error::PendingToolCalls(response.tool_calls));
         }
 
-        let url = format!(
-            "{}/conversations/{}/chat",
-            self.base_url, chat.conversation_id
-        );
+        Ok(response.text)
+    }
 
-        let response = self.client.post(&url).json(&chat).send().await?;
-
-        Self::check_status(response.status())?;
-
-        let stream = response.bytes_stream();
-        let (sender, receiver) = mpsc::channel(100);
-
-        tokio::spawn(async move {
-            let reader = StreamReader::new(stream.map(|result| {
-                result.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
-            }));
-            let buffered_reader = BufReader::new(reader.compat().into_inner());
-            let mut decoder = decode(buffered_reader.compat());
+    /// Runs a document as a stream, and also assembles the final result of the stream.
+    ///
+    /// Events are forwarded to the returned `EventStream` as they're received, while the
+    /// stream is accumulated internally. Once the stream completes, the assembled
+    /// `RunResponse` is sent on the returned oneshot receiver, which is useful to render
+    /// tokens live and persist the final result. The document is always run streaming.
+    ///
+    /// # Arguments
+    /// * `document` - The `RunDocument` to run.
+    ///
+    /// # Returns
+    /// * The live `EventStream` and a receiver for the final `RunResponse`. The receiver
+    ///   errors if the stream ended before the conversation could be identified.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use latitude_sdk::{Client, models::document::RunDocument};
+    /// use tokio_stream::StreamExt;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), latitude_sdk::error::Error> {
+    ///     let client = Client::builder("your_api_key".into()).project_id(123).build()?;
+    ///
+    ///     let document = RunDocument::<()>::builder()
+    ///         .path("Workers/EmotionAnalyzer".to_owned())
+    ///         .build();
+    ///
+    ///     let (mut events, final_response) = client.run_tee(document).await.unwrap();
+    ///     while let Some(event) = events.next().await {
+    ///         println!("Event: {:?}", event);
+    ///     }
+    ///
+    ///     println!("Final response: {:?}", final_response.await);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_tee<T>(
+        &self,
+        mut document: RunDocument<T>,
+    ) -> Result<(EventStream, oneshot::Receiver<RunResponse>), Error>
+    where
+        T: Serialize + std::fmt::Debug,
+    {
+        document.stream = true;
+
+        let mut events = match self.run(document).await? {
+            Response::Stream(events) => events,
+            Response::Json(_) => {
+                return Err(Error::ResponseFormatError(
+                    "Expected a streaming response".to_owned(),
+                ))
+            }
+        };
 
-            while let Some(event) = decoder.next().await {
-                match event {
-                    Ok(async_sse::Event::Message(message)) => {
-                        let data = message.data();
-                        let parsed_event = match message.name().as_str() {
-                            "latitude-event" => serde_json::from_slice(data)
-                                .map(Event::LatitudeEvent)
-                                .map_err(Error::from),
-                            "provider-event" => serde_json::from_slice(data)
-                                .map(Event::ProviderEvent)
-                                .map_err(Error::from),
-                            _ => Ok(Event::UnknownEvent),
-                        };
-
-                        if let Ok(event) = parsed_event {
-                            if sender.send(event).await.is_err() {
-                                break;
-                            }
-                        }
-                    }
-                    Ok(async_sse::Event::Retry(_)) => {}
-                    Err(e) => {
-                        error!("Streaming error: {:?}", e);
-                        break;
+        let (sender, receiver) = mpsc::channel(self.stream_buffer);
+        let (response_sender, response_receiver) = oneshot::channel();
+        let text = events.text_buffer();
+        let usage = events.usage_receiver();
+        let stream_id = events.stream_id().map(str::to_owned);
+        let canceller = events.canceller();
+
+        let task = Self::runtime_handle()?.spawn(async move {
+            let mut accumulator = StreamAccumulator::new();
+            let mut forwarding = true;
+
+            while let Some(event) = events.next().await {
+                let item = match event {
+                    Ok(event) => {
+                        accumulator.push(&event);
+                        Ok((event, events.raw().map(str::to_owned)))
                     }
+                    Err(e) => Err(e.into()),
+                };
+
+                // Keep accumulating even if the caller stopped consuming the live stream.
+                if forwarding && sender.send(item).await.is_err() {
+                    forwarding = false;
                 }
             }
-        });
 
-        Ok(Response::Stream(receiver))
+            drop(sender);
+            if let Some(response) = accumulator.run_response() {
+                let _ = response_sender.send(response);
+            }
+        });
 
-        /*         response
-        .json::<RunResponse>()
-        .await
-        .map(Response::Json)
-        .map_err(Error::from) */
+        Ok((
+            EventStream::new(receiver, text, usage, task.abort_handle())
+                .with_stream_id(stream_id)
+                .with_canceller(canceller),
+            response_receiver,
+        ))
     }
 
-    pub async fn get(&self, path: &str, options: Option<Options>) -> Result<Document, Error> {
-        let project_id = options
-            .as_ref()
-            .and_then(|opts| opts.project_id)
-            .or(self.project_id)
-            .ok_or_else(|| Error::ConfigError("Project ID is required".to_owned()))?;
+    /// Runs a document as a stream, splitting it into text and tool call channels.
+    ///
+    /// Text deltas are sent on the first channel as they're received, which suits display,
+    /// while tool calls are sent on the second channel once completed, which suits
+    /// execution. Tool calls streamed as `tool-call-delta` fragments are assembled before
+    /// they're sent. Other events and stream errors are dropped. The document is always run
+    /// streaming.
+    ///
+    /// Both channels are unbounded, so they can be drained in any order, e.g. all of the
+    /// text first, then the tool calls, without stalling the stream.
+    ///
+    /// # Arguments
+    /// * `document` - The `RunDocument` to run.
+    ///
+    /// # Returns
+    /// * The receivers of the text deltas and of the completed tool calls.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use latitude_sdk::{Client, models::document::RunDocument};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), latitude_sdk::error::Error> {
+    ///     let client = Client::builder("your_api_key".into()).project_id(123).build()?;
+    ///
+    ///     let document = RunDocument::<()>::builder()
+    ///         .path("Agents/Assistant".to_owned())
+    ///         .build();
+    ///
+    ///     let (mut text, mut tool_calls) = client.run_split(document).await.unwrap();
+    ///     while let Some(delta) = text.recv().await {
+    ///         print!("{}", delta);
+    ///     }
+    ///     while let Some(tool_call) = tool_calls.recv().await {
+    ///         println!("Calling {}", tool_call.tool_name);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_split<T>(
+        &self,
+        mut document: RunDocument<T>,
+    ) -> Result<
+        (
+            mpsc::UnboundedReceiver<String>,
+            mpsc::UnboundedReceiver<ToolCallEvent>,
+        ),
+        Error,
+    >
+    where
+        T: Serialize + std::fmt::Debug,
+    {
+        document.stream = true;
+
+        let mut events = match self.run(document).await? {
+            Response::Stream(events) => events,
+            Response::Json(_) => {
+                return Err(Error::ResponseFormatError(
+                    "Expected a streaming response".to_owned(),
+                ))
+            }
+        };
 
-        let version_id = options
-            .as_ref()
-            .and_then(|opts| opts.version_id.clone())
-            .or(self.version_id.clone())
-            .unwrap_or_else(|| "live".to_string());
+        // A bounded channel would block the task on the channel that isn't drained, and with
+        // it the other channel, whenever the consumer drains them one after the other.
+        let (text_sender, text_receiver) = mpsc::unbounded_channel();
+        let (tool_call_sender, tool_call_receiver) = mpsc::unbounded_channel();
 
-        let url = format!(
-            "{}/projects/{}/versions/{}/documents/{}",
-            self.base_url, project_id, version_id, path
-        );
+        Self::runtime_handle()?.spawn(async move {
+            let mut accumulator = StreamAccumulator::new();
 
-        let response = self.client.get(&url).send().await?;
+            while let Some(event) = events.next().await {
+                let Ok(event) = event else {
+                    continue;
+                };
 
-        Self::check_status(response.status())?;
+                if let Event::ProviderEvent(provider_event) = &event {
+                    if let ProviderEventType::TextDelta(delta) = &provider_event.event_type {
+                        let _ = text_sender.send(delta.text_delta.clone());
+                    }
+                }
 
-        response.json::<Document>().await.map_err(Error::from)
-    }
+                let completed = accumulator.completed_tool_calls().len();
+                accumulator.push(&event);
 
-    pub async fn log(&self, log: Log) -> Result<LogResponse, Error> {
-        let project_id = log
-            .options
-            .as_ref()
-            .and_then(|opts| opts.project_id)
-            .or(self.project_id)
-            .ok_or_else(|| Error::ConfigError("Project ID is required".to_owned()))?;
+                for tool_call in &accumulator.completed_tool_calls()[completed..] {
+                    let _ = tool_call_sender.send(tool_call.clone());
+                }
+            }
+        });
 
-        let version_id = log
-            .options
-            .as_ref()
-            .and_then(|opts| opts.version_id.clone())
-            .or(self.version_id.clone())
-            .unwrap_or_else(|| "live".to_string());
+        Ok((text_receiver, tool_call_receiver))
+    }
 
-        let url = format!(
-            "{}/projects/{}/versions/{}/documents/logs",
-            self.base_url, project_id, version_id
-        );
+    /// Runs a document once for every parameter set produced by a stream.
+    ///
+    /// Inputs are pulled lazily, so they may come from a file or a database cursor
+    /// without loading the whole dataset in memory, and at most `concurrency` runs are
+    /// in flight at once. Results are yielded in the order of their inputs. Dropping the
+    /// returned stream cancels the runs still in flight.
+    ///
+    /// A failing run yields its error and the batch goes on; see `run_batch_fail_fast`
+    /// to stop it at the first error instead.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the document to run.
+    /// * `inputs` - The stream of parameter sets, one per run.
+    /// * `concurrency` - The maximum number of concurrent runs.
+    ///
+    /// # Returns
+    /// * A stream of the `RunResponse` or error of every run.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use latitude_sdk::Client;
+    /// use serde::Serialize;
+    /// use tokio_stream::StreamExt;
+    ///
+    /// #[derive(Serialize, Debug, Default)]
+    /// struct Params {
+    ///     user_message: String,
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), latitude_sdk::error::Error> {
+    ///     let client = Client::builder("your_api_key".into()).project_id(123).build()?;
+    ///
+    ///     let inputs = tokio_stream::iter(["I love it", "I hate it"]).map(|message| Params {
+    ///         user_message: message.to_owned(),
+    ///     });
+    ///
+    ///     let results = client.run_batch("Workers/EmotionAnalyzer", inputs, 4);
+    ///     tokio::pin!(results);
+    ///
+    ///     while let Some(result) = results.next().await {
+    ///         println!("{:?}", result);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn run_batch<'a, T, S>(
+        &'a self,
+        path: &'a str,
+        inputs: S,
+        concurrency: usize,
+    ) -> impl Stream<Item = Result<RunResponse, Error>> + 'a
+    where
+        T: Serialize + std::fmt::Debug + 'a,
+        S: Stream<Item = T> + 'a,
+    {
+        self.run_batch_with(path, inputs, concurrency, false)
+    }
 
-        let response = self.client.post(&url).json(&log).send().await?;
+    /// Runs a document once for every parameter set produced by a stream, stopping at the
+    /// first error.
+    ///
+    /// This behaves like `run_batch`, except that the first failing run cancels the runs
+    /// in flight and stops the batch: its error is yielded right away, after the results
+    /// that came before it, and the stream ends without pulling any more inputs.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the document to run.
+    /// * `inputs` - The stream of parameter sets, one per run.
+    /// * `concurrency` - The maximum number of concurrent runs.
+    ///
+    /// # Returns
+    /// * A stream of the `RunResponse` of every run up to the first error, then that error.
+    pub fn run_batch_fail_fast<'a, T, S>(
+        &'a self,
+        path: &'a str,
+        inputs: S,
+        concurrency: usize,
+    ) -> impl Stream<Item = Result<RunResponse, Error>> + 'a
+    where
+        T: Serialize + std::fmt::Debug + 'a,
+        S: Stream<Item = T> + 'a,
+    {
+        self.run_batch_with(path, inputs, concurrency, true)
+    }
 
-        Self::check_status(response.status())?;
+    fn run_batch_with<'a, T, S>(
+        &'a self,
+        path: &'a str,
+        inputs: S,
+        concurrency: usize,
+        fail_fast: bool,
+    ) -> impl Stream<Item = Result<RunResponse, Error>> + 'a
+    where
+        T: Serialize + std::fmt::Debug + 'a,
+        S: Stream<Item = T> + 'a,
+    {
+        // A run cancelled by the failure of another yields `None`; the first error is kept
+        // aside so it is what the stream yields in place of the cancelled runs.
+        let cancelled = CancellationToken::new();
+        let first_error = Arc::new(Mutex::new(None));
 
-        response.json::<LogResponse>().await.map_err(Error::from)
-    }
+        let runs = {
+            let first_error = first_error.clone();
 
-    pub async fn eval(
-        &self,
-        conversation: &str,
-        eval: Option<Evaluation>,
-    ) -> Result<EvaluationResponse, Error> {
-        let url = format!("{}/conversations/{}/chat", self.base_url, conversation);
+            inputs.map(move |parameters| {
+                let cancelled = cancelled.clone();
+                let first_error = first_error.clone();
 
-        let mut response = self.client.post(&url);
+                async move {
+                    if cancelled.is_cancelled() {
+                        return None;
+                    }
 
-        if let Some(eval) = eval {
-            response = response.json(&eval);
-        }
+                    let run = std::pin::pin!(self.run_batch_item(path, parameters));
+                    let result = if fail_fast {
+                        match future::select(run, std::pin::pin!(cancelled.cancelled())).await {
+                            Either::Left((result, _)) => result,
+                            Either::Right(_) => return None,
+                        }
+                    } else {
+                        run.await
+                    };
+
+                    match result {
+                        Err(error) if fail_fast => {
+                            first_error
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .get_or_insert(error);
+                            cancelled.cancel();
+                            None
+                        }
+                        result => Some(result),
+                    }
+                }
+            })
+        };
 
-        let response = response.send().await?;
+        let results = futures_util::StreamExt::buffered(runs, concurrency.max(1));
 
-        Self::check_status(response.status())?;
+        futures_util::StreamExt::scan(results, false, move |failed, result| {
+            if *failed {
+                return future::ready(None);
+            }
 
-        response
-            .json::<EvaluationResponse>()
-            .await
-            .map_err(Error::from)
+            future::ready(Some(result.unwrap_or_else(|| {
+                *failed = true;
+                Err(first_error
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .take()
+                    .unwrap_or_else(|| Error::Other("batch cancelled".to_owned())))
+            })))
+        })
     }
 
-    pub(crate) fn check_status(status: StatusCode) -> Result<(), Error> {
-        match status {
-            StatusCode::TOO_MANY_REQUESTS => {
-                Err(Error::LatitudeError(LatitudeErrorCodes::RateLimitError))
-            }
-            StatusCode::UNAUTHORIZED => {
-                Err(Error::LatitudeError(LatitudeErrorCodes::UnauthorizedError))
-            }
-            StatusCode::FORBIDDEN => Err(Error::LatitudeError(LatitudeErrorCodes::ForbiddenError)),
-            StatusCode::BAD_REQUEST => {
-                Err(Error::LatitudeError(LatitudeErrorCodes::BadRequestError))
-            }
-            StatusCode::NOT_FOUND => Err(Error::LatitudeError(LatitudeErrorCodes::NotFoundError)),
-            StatusCode::CONFLICT => Err(Error::LatitudeError(LatitudeErrorCodes::ConflictError)),
-            StatusCode::UNPROCESSABLE_ENTITY => Err(Error::LatitudeError(
-                LatitudeErrorCodes::UnprocessableEntityError,
+    async fn run_batch_item<T>(&self, path: &str, parameters: T) -> Result<RunResponse, Error>
+    where
+        T: Serialize + std::fmt::Debug,
+    {
+        let document = RunDocument::builder()
+            .path(path.to_owned())
+            .parameters(parameters)
+            .build();
+
+        match self.run(document).await? {
+            Response::Json(response) => Ok(response),
+            Response::Stream(_) => Err(Error::ResponseFormatError(
+                "Expected a JSON response".to_owned(),
             )),
-            _ => Ok(()),
         }
     }
-}
-
-/// Builder for configuring and creating a `Client` instance.
-///
-/// The `ClientBuilder` provides a fluent interface for setting optional parameters,
-/// allowing customization of `project_id`, `version_id`, and `base_url`. Once all
-/// desired parameters are set, call `build` to create a `Client` instance.
-pub struct ClientBuilder {
-    api_key: String,
-    project_id: Option<u64>,
-    version_id: Option<String>,
-    base_url: String,
-}
 
-impl ClientBuilder {
-    /// Sets the `project_id` for the `Client`.
+    /// Runs a document as a stream, writing the text deltas to a writer as they arrive.
     ///
-    /// This `project_id` is used as the default project for API requests.
+    /// The writer is flushed after every delta so the text shows up immediately. The
+    /// document is always run streaming.
     ///
     /// # Arguments
+    /// * `document` - The `RunDocument` to run.
+    /// * `writer` - The writer receiving the streamed text.
     ///
-    /// * `project_id` - The ID of the project.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use latitude_sdk::Client;
-    ///
-    /// let client_builder = Client::builder("your_api_key".into())
-    ///     .project_id(123);
-    /// ```
-    pub fn project_id(mut self, project_id: u64) -> Self {
-        self.project_id = Some(project_id);
-        self
+    /// # Returns
+    /// * The `RunResponse` assembled from the stream, the first error met while receiving
+    ///   the stream, or `Error::IoError` if writing fails.
+    pub async fn run_to_writer<T, W>(
+        &self,
+        mut document: RunDocument<T>,
+        writer: &mut W,
+    ) -> Result<RunResponse, Error>
+    where
+        T: Serialize + std::fmt::Debug,
+        W: Write,
+    {
+        document.stream = true;
+
+        let mut events = match self.run(document).await? {
+            Response::Stream(events) => events,
+            Response::Json(_) => {
+                return Err(Error::ResponseFormatError(
+                    "Expected a streaming response".to_owned(),
+                ))
+            }
+        };
+
+        let mut accumulator = StreamAccumulator::new();
+        while let Some(event) = events.next().await {
+            let event = event?;
+            if let Some(delta) = event.text_delta() {
+                writer.write_all(delta.as_bytes())?;
+                writer.flush()?;
+            }
+            accumulator.push(&event);
+        }
+
+        accumulator.run_response().ok_or_else(|| {
+            Error::ResponseFormatError("The stream ended without a response".to_owned())
+        })
     }
 
-    /// Sets the `version_id` for the `Client`.
+    /// Runs a document as a stream, printing the text to stdout as it arrives.
     ///
-    /// This `version_id` represents the version of the project or document and
-    /// will be used in API requests.
+    /// This suits quick CLI demos: the text is flushed as it's generated, and a trailing
+    /// newline is printed if the text doesn't end with one, so the shell prompt starts on
+    /// its own line.
     ///
     /// # Arguments
+    /// * `document` - The `RunDocument` to run.
     ///
-    /// * `version_id` - The UUID of the version.
+    /// # Returns
+    /// * The `RunResponse` assembled from the stream.
     ///
-    /// # Example
+    /// # Examples
+    /// ```rust,no_run
+    /// use latitude_sdk::{Client, models::document::RunDocument};
     ///
-    /// ```
-    /// use latitude_sdk::Client;
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), latitude_sdk::error::Error> {
+    ///     let client = Client::builder("your_api_key".into()).project_id(123).build()?;
     ///
-    /// let client_builder = Client::builder("your_api_key".into())
-    ///     .version_id("version-uuid".to_string());
+    ///     let document = RunDocument::<()>::builder()
+    ///         .path("Workers/Storyteller".to_owned())
+    ///         .build();
+    ///
+    ///     let response = client.run_print(document).await.unwrap();
+    ///     eprintln!("{:?} tokens", response.response.usage.total_tokens);
+    ///
+    ///     Ok(())
+    /// }
     /// ```
-    pub fn version_id(mut self, version_id: String) -> Self {
-        self.version_id = Some(version_id);
-        self
+    pub async fn run_print<T>(&self, document: RunDocument<T>) -> Result<RunResponse, Error>
+    where
+        T: Serialize + std::fmt::Debug,
+    {
+        self.print_to(document, &mut std::io::stdout()).await
     }
 
-    /// Sets a custom `base_url` for the API endpoint.
-    ///
-    /// This is useful if the API endpoint changes or if using a mock server
-    /// for testing purposes.
+    /// Writes the streamed text to `writer` like `run_print` writes it to stdout.
+    async fn print_to<T, W>(
+        &self,
+        document: RunDocument<T>,
+        writer: &mut W,
+    ) -> Result<RunResponse, Error>
+    where
+        T: Serialize + std::fmt::Debug,
+        W: Write,
+    {
+        let response = self.run_to_writer(document, writer).await?;
+
+        if !response.response.text.is_empty() && !response.response.text.ends_with('\n') {
+            writeln!(writer)?;
+            writer.flush()?;
+        }
+
+        Ok(response)
+    }
+
+    /// Continues a conversation with the messages of a `Chat`.
     ///
     /// # Arguments
+    /// * `chat` - The `Chat` carrying the conversation ID and the new messages.
     ///
-    /// * `base_url` - The base URL of the API as a string.
+    /// # Returns
+    /// * A `Response::Stream` of the events if the chat is streamed, or otherwise a
+    ///   `Response::Json` with the response.
+    pub async fn chat(&self, chat: Chat) -> Result<Response, Error> {
+        if chat.stream {
+            Self::runtime_handle()?;
+        }
+
+        debug!(conversation = %chat.conversation_id, "Sending chat");
+        self.trace_messages(&chat.messages);
+
+        let url = format!(
+            "{}/conversations/{}/chat",
+            self.base_url, chat.conversation_id
+        );
+
+        let request = self
+            .request(Method::POST, &url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(self.json_body(&chat)?);
+        let response = self.send(request, None).await?;
+
+        let response = Self::check_response(response).await?;
+
+        if chat.stream {
+            return self.stream_events(response).await;
+        }
+
+        self.run_response(response).await.map(Response::Json)
+    }
+
+    /// Sends a chat message and assembles the assistant's reply from the stream.
     ///
-    /// # Example
+    /// Unlike the streamed text alone, the returned message includes the tool calls the
+    /// assistant made, so agent loops can execute them and send their results back. The
+    /// chat is always streamed.
     ///
-    /// ```
-    /// use latitude_sdk::Client;
+    /// # Arguments
+    /// * `chat` - The `Chat` continuing the conversation.
     ///
-    /// let client_builder = Client::builder("your_api_key".into())
-    ///     .base_url("https://custom.url/api".to_string());
+    /// # Returns
+    /// * The assistant's `Message`, or the first error met while receiving the stream.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use latitude_sdk::{models::chat::Chat, Client};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), latitude_sdk::error::Error> {
+    ///     let client = Client::builder("your_api_key".into()).build()?;
+    ///
+    ///     let chat = Chat::user("conversation-uuid".to_owned(), "What's the weather?");
+    ///     let message = client.chat_message(chat).await.unwrap();
+    ///
+    ///     for tool_call in message.tool_calls.unwrap_or_default() {
+    ///         println!("Calling {} with {}", tool_call.name, tool_call.arguments);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
     /// ```
-    pub fn base_url(mut self, base_url: String) -> Self {
-        self.base_url = base_url;
-        self
+    pub async fn chat_message(&self, mut chat: Chat) -> Result<models::event::Message, Error> {
+        chat.stream = true;
+
+        let mut events = match self.chat(chat).await? {
+            Response::Stream(events) => events,
+            Response::Json(_) => {
+                return Err(Error::ResponseFormatError(
+                    "Expected a streaming response".to_owned(),
+                ))
+            }
+        };
+
+        let mut accumulator = StreamAccumulator::new();
+        while let Some(event) = events.next().await {
+            accumulator.push(&event?);
+        }
+
+        Ok(accumulator.assistant_message())
     }
 
-    /// Builds and returns a new `Client` instance.
+    pub async fn get(&self, path: &str, options: Option<Options>) -> Result<Document, Error> {
+        let project_id = options
+            .as_ref()
+            .and_then(|opts| opts.project_id)
+            .or(self.project_id)
+            .ok_or_else(|| Error::ConfigError("Project ID is required".to_owned()))?;
+
+        let version_id = self.resolve_version_id(options.as_ref());
+
+        let url = format!(
+            "{}/projects/{}/versions/{}/documents/{}",
+            self.base_url, project_id, version_id, path
+        );
+
+        let response = self
+            .send(self.request(Method::GET, &url), options.as_ref())
+            .await?;
+
+        let response = Self::check_response(response).await?;
+
+        self.json::<Document>(response).await
+    }
+
+    /// Lists the documents of a project version.
     ///
-    /// After setting the necessary parameters, call `build` to create the `Client`.
-    /// Once built, the `Client` can be used to interact with the Latitude API.
+    /// # Arguments
+    /// * `options` - Optional project and version overrides; the version defaults to `live`.
     ///
-    /// # Example
+    /// # Returns
+    /// * All documents of the version.
+    pub async fn list_documents(&self, options: Option<Options>) -> Result<Vec<Document>, Error> {
+        let project_id = options
+            .as_ref()
+            .and_then(|opts| opts.project_id)
+            .or(self.project_id)
+            .ok_or_else(|| Error::ConfigError("Project ID is required".to_owned()))?;
+
+        let version_id = self.resolve_version_id(options.as_ref());
+
+        let url = format!(
+            "{}/projects/{}/versions/{}/documents",
+            self.base_url, project_id, version_id
+        );
+
+        let response = self
+            .send(self.request(Method::GET, &url), options.as_ref())
+            .await?;
+
+        let response = Self::check_response(response).await?;
+
+        self.json::<Vec<Document>>(response).await
+    }
+
+    /// Lists the documents of a project version within a folder.
     ///
-    /// ```
+    /// The documents are filtered on the client. A trailing `/` or `/*` on the prefix is
+    /// ignored, so `Workers`, `Workers/` and `Workers/*` all match `Workers/EmotionAnalyzer`
+    /// and `Workers/Nested/Summarizer`, but not `WorkersArchive/Old`.
+    ///
+    /// # Arguments
+    /// * `prefix` - The path of the folder.
+    /// * `options` - Optional project and version overrides; the version defaults to `live`.
+    ///
+    /// # Returns
+    /// * The documents of the version under the folder.
+    pub async fn list_documents_under(
+        &self,
+        prefix: &str,
+        options: Option<Options>,
+    ) -> Result<Vec<Document>, Error> {
+        let folder = prefix.trim_end_matches('*').trim_end_matches('/');
+
+        let mut documents = self.list_documents(options).await?;
+        documents.retain(|document| {
+            document
+                .path
+                .strip_prefix(folder)
+                .is_some_and(|rest| folder.is_empty() || rest.starts_with('/'))
+        });
+
+        Ok(documents)
+    }
+
+    /// Runs every document within a folder with the same parameters.
+    ///
+    /// The documents are listed with `list_documents_under`, then run without streaming,
+    /// at most `concurrency` at once. A failing run doesn't stop the others.
+    ///
+    /// # Arguments
+    /// * `prefix` - The path of the folder.
+    /// * `parameters` - The parameters passed to every document.
+    /// * `options` - Optional project and version overrides, for the listing and the runs.
+    /// * `concurrency` - The maximum number of concurrent runs.
+    ///
+    /// # Returns
+    /// * The path of every document under the folder along with the result of its run,
+    ///   in the order they were listed, or the error met listing them.
+    ///
+    /// # Examples
+    /// ```rust,no_run
     /// use latitude_sdk::Client;
+    /// use serde_json::json;
     ///
-    /// let client = Client::builder("your_api_key".into())
-    ///     .project_id(123)
-    ///     .version_id("version-uuid".to_string())
-    ///     .base_url("https://custom.url/api".to_string())
-    ///     .build();
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), latitude_sdk::error::Error> {
+    ///     let client = Client::builder("your_api_key".into()).project_id(123).build()?;
+    ///
+    ///     let results = client
+    ///         .run_all_under("Workers/*", Some(json!({ "user_message": "Hello" })), None, 4)
+    ///         .await
+    ///         .expect("Failed to list documents");
+    ///
+    ///     for (path, result) in results {
+    ///         println!("{}: {:?}", path, result.map(|response| response.response.text));
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
     /// ```
-    pub fn build(self) -> Client {
-        Client::new(
-            self.api_key,
-            self.project_id,
-            self.version_id,
-            Some(self.base_url),
+    pub async fn run_all_under<T>(
+        &self,
+        prefix: &str,
+        parameters: Option<T>,
+        options: Option<Options>,
+        concurrency: usize,
+    ) -> Result<Vec<(String, Result<RunResponse, Error>)>, Error>
+    where
+        T: Serialize + Clone + std::fmt::Debug,
+    {
+        let documents = self.list_documents_under(prefix, options.clone()).await?;
+
+        let runs = documents.into_iter().map(|document| {
+            let mut run = RunDocument::builder().path(document.path.clone());
+            if let Some(parameters) = &parameters {
+                run = run.parameters(parameters.clone());
+            }
+            if let Some(options) = &options {
+                run = run.options(options.clone());
+            }
+            let run = run.build();
+
+            async move {
+                let result = match self.run(run).await {
+                    Ok(Response::Json(response)) => Ok(response),
+                    Ok(Response::Stream(_)) => Err(Error::ResponseFormatError(
+                        "Expected a JSON response".to_owned(),
+                    )),
+                    Err(error) => Err(error),
+                };
+                (document.path, result)
+            }
+        });
+
+        Ok(
+            futures_util::StreamExt::buffered(futures_util::stream::iter(runs), concurrency.max(1))
+                .collect()
+                .await,
         )
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::str::FromStr;
+    /// Fetches the metadata of a commit, e.g. one referenced by a document's or log's
+    /// `commit_id`.
+    ///
+    /// # Arguments
+    /// * `project_id` - The ID of the project the commit belongs to.
+    /// * `commit_id` - The ID of the commit.
+    ///
+    /// # Returns
+    /// * The `Commit` with its UUID, title and creation time.
+    pub async fn get_commit(&self, project_id: u64, commit_id: i64) -> Result<Commit, Error> {
+        let url = format!(
+            "{}/projects/{}/commits/{}",
+            self.base_url, project_id, commit_id
+        );
 
-    use super::*;
-    use httpmock::Method::POST;
-    use httpmock::Mock;
-    use httpmock::MockServer;
-    use models::event::Message;
-    use models::event::{ChainStep, Config, LatitudeEventType, ProviderEventType, TextDelta};
-    use models::message::Message as MessageMessage;
-    use models::message::Role;
-    use models::options::Options;
-    use serde_json::json;
-    use uuid::Uuid;
+        let response = self.send(self.request(Method::GET, &url), None).await?;
 
-    // Helper function to setup a client for testing
-    fn setup_client(
-        api_key: &str,
-        project_id: Option<u64>,
-        version_id: Option<&str>,
-        base_url: Option<&str>,
-    ) -> Client {
-        let mut client_builder = Client::builder(api_key.into());
-        if let Some(pid) = project_id {
-            client_builder = client_builder.project_id(pid);
+        let response = Self::check_response(response).await?;
+
+        self.json::<Commit>(response).await
+    }
+
+    /// Fetches a document as it was in each of the given commits.
+    ///
+    /// Every commit is looked up with `get_commit`, then the document is fetched at the
+    /// commit's UUID with `get`, so prompt changes can be reviewed via each version's
+    /// `commit_id` and `merged_at`.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the document.
+    /// * `commit_ids` - The IDs of the commits to fetch the document at, in order.
+    /// * `options` - Optional project override; its version is replaced per commit.
+    ///
+    /// # Returns
+    /// * The document at each commit, in the order of `commit_ids`.
+    pub async fn document_history(
+        &self,
+        path: &str,
+        commit_ids: &[i64],
+        options: Option<Options>,
+    ) -> Result<Vec<Document>, Error> {
+        let project_id = options
+            .as_ref()
+            .and_then(|opts| opts.project_id)
+            .or(self.project_id)
+            .ok_or_else(|| Error::ConfigError("Project ID is required".to_owned()))?;
+
+        let mut history = Vec::with_capacity(commit_ids.len());
+        for &commit_id in commit_ids {
+            let commit = self.get_commit(project_id, commit_id).await?;
+
+            let mut options = options.clone().unwrap_or_default();
+            options.project_id = Some(project_id);
+            options.version_id = Some(commit.uuid);
+
+            history.push(self.get(path, Some(options)).await?);
         }
-        if let Some(vid) = version_id {
-            client_builder = client_builder.version_id(vid.to_string());
+
+        Ok(history)
+    }
+
+    pub async fn log(&self, log: Log) -> Result<LogResponse, Error> {
+        let project_id = log
+            .options
+            .as_ref()
+            .and_then(|opts| opts.project_id)
+            .or(self.project_id)
+            .ok_or_else(|| Error::ConfigError("Project ID is required".to_owned()))?;
+
+        let version_id = self.resolve_version_id(log.options.as_ref());
+
+        debug!(
+            path = %log.path,
+            response = %self.loggable(&log.response),
+            "Creating log"
+        );
+        self.trace_messages(&log.messages);
+
+        let url = format!(
+            "{}/projects/{}/versions/{}/documents/logs",
+            self.base_url, project_id, version_id
+        );
+
+        let response = self
+            .send(
+                self.request(Method::POST, &url)
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(self.json_body(&log)?),
+                log.options.as_ref(),
+            )
+            .await?;
+
+        let response = Self::check_response(response).await?;
+
+        self.json::<LogResponse>(response).await
+    }
+
+    /// Cancels the run of a conversation on the server.
+    ///
+    /// Aborting a stream locally only stops receiving it; this stops the generation
+    /// itself. See `EventStream::cancel_with_reason` to do both at once.
+    ///
+    /// # Arguments
+    /// * `conversation` - The UUID of the conversation whose run should be cancelled.
+    /// * `reason` - Why the run is cancelled, recorded by the server for audits.
+    ///
+    /// # Returns
+    /// * `Ok(())` once the run is cancelled, or the error returned by the API.
+    pub async fn cancel_run(&self, conversation: &str, reason: Option<&str>) -> Result<(), Error> {
+        let url = format!("{}/conversations/{}/cancel", self.base_url, conversation);
+
+        let mut request = self.request(Method::POST, &url);
+
+        if let Some(reason) = reason {
+            request = request.json(&serde_json::json!({ "reason": reason }));
+        }
+
+        let response = self.send(request, None).await?;
+
+        Self::check_response(response).await?;
+
+        Ok(())
+    }
+
+    /// Triggers evaluations of a conversation.
+    ///
+    /// # Arguments
+    /// * `conversation` - The UUID of the conversation to evaluate.
+    /// * `eval` - The evaluations to run, or `None` to run all those connected to the document.
+    ///
+    /// # Returns
+    /// * `EvaluationResponse` - The triggered evaluations; see `wait_for_evaluation` for
+    ///   their results.
+    pub async fn eval(
+        &self,
+        conversation: &str,
+        eval: Option<Evaluation>,
+    ) -> Result<EvaluationResponse, Error> {
+        let url = format!("{}/conversations/{}/evaluate", self.base_url, conversation);
+
+        let mut response = self.request(Method::POST, &url);
+
+        if let Some(eval) = eval {
+            response = response.json(&eval);
+        }
+
+        let response = self.send(response, None).await?;
+
+        let response = Self::check_response(response).await?;
+
+        self.json::<EvaluationResponse>(response).await
+    }
+
+    /// Fetches the result of a specific evaluation for a conversation.
+    ///
+    /// Evaluations triggered with `eval` may be computed asynchronously, in which case the
+    /// returned result has a `Pending` status until it's ready.
+    ///
+    /// # Arguments
+    /// * `conversation` - The UUID of the conversation that was evaluated.
+    /// * `evaluation_uuid` - The UUID of the evaluation whose result should be fetched.
+    ///
+    /// # Returns
+    /// * `EvaluationResult` - The current state of the evaluation result.
+    pub async fn get_evaluation_result(
+        &self,
+        conversation: &str,
+        evaluation_uuid: &str,
+    ) -> Result<EvaluationResult, Error> {
+        let url = format!(
+            "{}/conversations/{}/evaluations/{}/result",
+            self.base_url, conversation, evaluation_uuid
+        );
+
+        let response = self.send(self.request(Method::GET, &url), None).await?;
+
+        let response = Self::check_response(response).await?;
+
+        self.json::<EvaluationResult>(response).await
+    }
+
+    /// Polls for an evaluation result until it's ready or the timeout elapses.
+    ///
+    /// Polling starts after a short delay which doubles on each attempt, capped at a few seconds.
+    ///
+    /// # Arguments
+    /// * `conversation` - The UUID of the conversation that was evaluated.
+    /// * `evaluation_uuid` - The UUID of the evaluation whose result should be awaited.
+    /// * `timeout` - The maximum time to wait for the result.
+    ///
+    /// # Returns
+    /// * `EvaluationResult` - The completed (or failed) evaluation result, or `Error::Other`
+    ///   if it's still pending when the timeout elapses.
+    pub async fn wait_for_evaluation(
+        &self,
+        conversation: &str,
+        evaluation_uuid: &str,
+        timeout: Duration,
+    ) -> Result<EvaluationResult, Error> {
+        let deadline = Instant::now() + timeout;
+        let mut delay = EVALUATION_POLL_INITIAL_DELAY;
+
+        loop {
+            let result = self
+                .get_evaluation_result(conversation, evaluation_uuid)
+                .await?;
+
+            if result.is_ready() {
+                return Ok(result);
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::Other(format!(
+                    "Timed out waiting for evaluation {}",
+                    evaluation_uuid
+                )));
+            }
+
+            tokio::time::sleep(delay.min(deadline - now)).await;
+            delay = (delay * 2).min(EVALUATION_POLL_MAX_DELAY);
+        }
+    }
+
+    /// Turns a streaming HTTP response into a `Response::Stream` of decoded events.
+    ///
+    /// A request that fails validation may answer with a JSON error body instead of an
+    /// event stream, so a JSON `Content-Type` is surfaced as an error rather than fed
+    /// into the SSE decoder.
+    async fn stream_events(&self, response: reqwest::Response) -> Result<Response, Error> {
+        let is_json = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("application/json"));
+
+        if is_json {
+            let body = response.bytes().await?;
+            return Err(Self::stream_json_error(&body));
+        }
+
+        let stream_id = response
+            .headers()
+            .get(STREAM_ID)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+        let timing = response.extensions().get::<RequestTiming>().copied();
+        let stream = response.bytes_stream();
+        let (sender, receiver) = response::channel(self.slow_consumer_policy, self.stream_buffer);
+        let on_stream_progress = self.on_stream_progress.clone();
+        let on_metrics = self.on_metrics.clone();
+        let capture_raw_events = self.capture_raw_events;
+        let event_names = self.event_names.clone();
+        let text = Arc::new(Mutex::new(String::new()));
+        let task_text = text.clone();
+        let (usage_sender, usage) = watch::channel(None);
+        let conversation = Arc::new(Mutex::new(None));
+        let task_conversation = conversation.clone();
+
+        let stream_idle_timeout = self.stream_idle_timeout;
+        let last_frame = Arc::new(Mutex::new(Instant::now()));
+        let frame_clock = last_frame.clone();
+
+        let stream_task = async move {
+            let reader = StreamReader::new(stream::skip_prelude(stream.map(move |result| {
+                *frame_clock.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+                result.map_err(std::io::Error::other)
+            })));
+            let events = stream::decode_with_raw(reader.compat(), event_names);
+            let mut events = std::pin::pin!(events);
+
+            let mut last_usage = None;
+            let mut first_event = None;
+
+            // Comment frames such as the gateway's `: keep-alive` are skipped by the
+            // decoder, so they never reach the consumer nor end the stream.
+            while let Some(event) =
+                Self::next_or_idle(events.as_mut(), stream_idle_timeout, &last_frame).await
+            {
+                match event {
+                    Ok((event, data)) => {
+                        first_event.get_or_insert_with(Instant::now);
+
+                        if let Event::LatitudeEvent(LatitudeEvent {
+                            event_type: LatitudeEventType::ChainStep(step),
+                        }) = &event
+                        {
+                            *task_conversation.lock().unwrap_or_else(|e| e.into_inner()) =
+                                Some(step.uuid.to_string());
+                        }
+
+                        if let Some(usage) = event.usage() {
+                            if let Some(callback) = &on_stream_progress {
+                                callback(usage);
+                            }
+
+                            if matches!(
+                                &event,
+                                Event::LatitudeEvent(LatitudeEvent {
+                                    event_type: LatitudeEventType::ChainComplete(_),
+                                })
+                            ) {
+                                usage_sender.send_replace(Some(usage.clone()));
+                            }
+                            last_usage = Some(usage.clone());
+                        }
+
+                        if let Some(delta) = event.text_delta() {
+                            task_text
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .push_str(delta);
+                        }
+
+                        let raw =
+                            capture_raw_events.then(|| String::from_utf8_lossy(&data).into_owned());
+
+                        if !sender.send(Ok((event, raw))).await {
+                            break;
+                        }
+                    }
+                    // Payloads that fail to parse are reported without ending the stream.
+                    Err(e @ Error::SerializationError(_)) => {
+                        if !sender.send(Err(e.into())).await {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        error!("Streaming error: {:?}", e);
+                        sender.send(Err(e.into())).await;
+                        break;
+                    }
+                }
+            }
+
+            if usage_sender.borrow().is_none() {
+                usage_sender.send_replace(last_usage);
+            }
+
+            if let (Some(timing), Some(callback)) = (timing, &on_metrics) {
+                callback(&timing.finish(first_event));
+            }
+        };
+
+        let task = {
+            let mut tasks = self.stream_tasks.lock().unwrap_or_else(|e| e.into_inner());
+            // `shutdown` may have taken the set while the request was in flight; the flag
+            // is set under this lock, so a task spawned here is always drained.
+            if self.shut_down.load(Ordering::Relaxed) {
+                return Err(Error::ConfigError("The client is shut down".to_owned()));
+            }
+            // Reap the tasks of finished streams so the set doesn't grow unbounded.
+            while tasks.0.try_join_next().is_some() {}
+            tasks.0.spawn_on(stream_task, &Self::runtime_handle()?)
+        };
+
+        Ok(Response::Stream(
+            EventStream::new(receiver, text, usage, task)
+                .with_stream_id(stream_id)
+                .with_canceller(Some(Arc::new(RunCanceller::new(
+                    self.clone(),
+                    conversation,
+                )))),
+        ))
+    }
+
+    /// Waits for the next decoded event, failing once no frame arrived for `idle_timeout`.
+    ///
+    /// Frames the decoder skips, such as keep-alive comments, still count as activity.
+    async fn next_or_idle<S>(
+        mut events: std::pin::Pin<&mut S>,
+        idle_timeout: Option<Duration>,
+        last_frame: &Mutex<Instant>,
+    ) -> Option<Result<(Event, Vec<u8>), Error>>
+    where
+        S: Stream<Item = Result<(Event, Vec<u8>), Error>>,
+    {
+        let Some(idle_timeout) = idle_timeout else {
+            return events.next().await;
+        };
+
+        // The wait only starts now, so time spent waiting on a slow consumer isn't idle.
+        let waiting_since = Instant::now();
+        let last_activity =
+            || (*last_frame.lock().unwrap_or_else(|e| e.into_inner())).max(waiting_since);
+
+        loop {
+            let deadline = last_activity() + idle_timeout;
+            match tokio::time::timeout_at(deadline.into(), events.next()).await {
+                Ok(event) => return event,
+                // A frame arrived in the meantime, which pushed the deadline back.
+                Err(_) if last_activity().elapsed() < idle_timeout => continue,
+                Err(_) => return Some(Err(Error::Other("stream idle timeout".to_owned()))),
+            }
+        }
+    }
+
+    /// Shuts the client down, draining the streams still in flight.
+    ///
+    /// New requests fail with `Error::ConfigError` from then on, on this client and all its
+    /// clones. The background tasks of in-flight streams get up to `timeout` to finish,
+    /// after which the remaining ones are aborted, ending their streams.
+    /// A streamed request still awaiting its response fails as well once it arrives, rather
+    /// than starting a stream that wouldn't be drained.
+    ///
+    /// # Arguments
+    /// * `timeout` - How long to wait for in-flight streams to complete.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use latitude_sdk::Client;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), latitude_sdk::error::Error> {
+    ///     let client = Client::builder("your_api_key".into()).project_id(123).build()?;
+    ///
+    ///     // ... serve requests ...
+    ///
+    ///     client.shutdown(Duration::from_secs(10)).await;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn shutdown(&self, timeout: Duration) {
+        let mut tasks = {
+            let mut tasks = self.stream_tasks.lock().unwrap_or_else(|e| e.into_inner());
+            self.shut_down.store(true, Ordering::Relaxed);
+            std::mem::take(&mut *tasks)
+        };
+
+        let drained = tokio::time::timeout(timeout, async {
+            while tasks.0.join_next().await.is_some() {}
+        })
+        .await;
+
+        if drained.is_err() {
+            tasks.0.shutdown().await;
+        }
+    }
+
+    /// Returns the text as it may appear in tracing output.
+    ///
+    /// With `redact_content` on, the text is replaced by a placeholder carrying only its
+    /// length in characters, e.g. `<redacted:142>`.
+    fn loggable<'a>(&self, text: &'a str) -> std::borrow::Cow<'a, str> {
+        if self.redact_content {
+            format!("<redacted:{}>", text.chars().count()).into()
+        } else {
+            text.into()
+        }
+    }
+
+    /// Traces the role and content of each message sent to the API.
+    fn trace_messages(&self, messages: &[Message]) {
+        for message in messages {
+            let content = message
+                .content
+                .iter()
+                .filter_map(Content::as_text)
+                .collect::<String>();
+
+            debug!(
+                role = %String::from(message.role.clone()),
+                content = %self.loggable(&content),
+                "Sending message"
+            );
+        }
+    }
+
+    /// Returns the handle of the current tokio runtime, which drives streamed responses.
+    ///
+    /// Streaming spawns a task decoding the events, so it requires a tokio runtime;
+    /// without one an `Error::ConfigError` is returned instead of panicking.
+    fn runtime_handle() -> Result<tokio::runtime::Handle, Error> {
+        tokio::runtime::Handle::try_current()
+            .map_err(|_| Error::ConfigError("no tokio runtime".to_owned()))
+    }
+
+    /// Builds an `Error` from a JSON body received where an event stream was expected.
+    fn stream_json_error(body: &[u8]) -> Error {
+        let value: serde_json::Value = match serde_json::from_slice(body) {
+            Ok(value) => value,
+            Err(e) => return Error::from(e),
+        };
+
+        let message = value
+            .get("message")
+            .and_then(|message| message.as_str())
+            .map(str::to_owned)
+            .unwrap_or_else(|| value.to_string());
+
+        Error::ResponseFormatError(format!(
+            "Expected an event stream but received a JSON error: {}",
+            message
+        ))
+    }
+
+    /// Checks a successful response body for a top-level `error` object.
+    ///
+    /// Some gateways answer provider-level failures with a 2xx status and an
+    /// `{ "error": { "code": ..., "message": ... } }` body. Known run error codes map to
+    /// `Error::RunError`, anything else to `Error::Other` with the reported message.
+    pub(crate) fn check_error_body(body: &serde_json::Value) -> Result<(), Error> {
+        let Some(error) = body.get("error").filter(|error| error.is_object()) else {
+            return Ok(());
+        };
+
+        let message = error
+            .get("message")
+            .and_then(|message| message.as_str())
+            .unwrap_or("Unknown error");
+
+        error!("Run failed with error body: {}", message);
+
+        match error
+            .get("code")
+            .cloned()
+            .and_then(|code| serde_json::from_value::<RunErrorCodes>(code).ok())
+        {
+            Some(code) => Err(Error::RunError(code)),
+            None => Err(Error::Other(message.to_owned())),
+        }
+    }
+
+    /// Checks the status of a response, surfacing the API's error body on failures.
+    ///
+    /// A rate-limited response maps to `Error::RateLimited`, carrying the delay of its
+    /// `Retry-After` header. Another failed response whose body is an
+    /// `ApiErrorJsonResponse` maps to `Error::ApiResponse`, keeping the server's message and
+    /// error code; any other failed response maps to an error by its status, see
+    /// `check_status`.
+    ///
+    /// # Returns
+    /// The response if its status isn't an error, otherwise the error it describes.
+    pub(crate) async fn check_response(
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response, Error> {
+        let Err(error) = Self::check_status(response.status()) else {
+            return Ok(response);
+        };
+
+        if let Error::RateLimited { .. } = error {
+            return Err(Error::RateLimited {
+                retry_after: Self::retry_after(response.headers()),
+            });
+        }
+
+        let body = response.bytes().await?;
+        Err(serde_json::from_slice::<ApiErrorJsonResponse>(&body)
+            .map(|error| Error::ApiResponse(Box::new(error)))
+            .unwrap_or(error))
+    }
+
+    pub(crate) fn check_status(status: StatusCode) -> Result<(), Error> {
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => Err(Error::RateLimited { retry_after: None }),
+            StatusCode::UNAUTHORIZED => {
+                Err(Error::LatitudeError(LatitudeErrorCodes::UnauthorizedError))
+            }
+            StatusCode::FORBIDDEN => Err(Error::LatitudeError(LatitudeErrorCodes::ForbiddenError)),
+            StatusCode::BAD_REQUEST => {
+                Err(Error::LatitudeError(LatitudeErrorCodes::BadRequestError))
+            }
+            StatusCode::NOT_FOUND => Err(Error::LatitudeError(LatitudeErrorCodes::NotFoundError)),
+            StatusCode::CONFLICT => Err(Error::LatitudeError(LatitudeErrorCodes::ConflictError)),
+            StatusCode::UNPROCESSABLE_ENTITY => Err(Error::LatitudeError(
+                LatitudeErrorCodes::UnprocessableEntityError,
+            )),
+            status if status.is_server_error() => {
+                Err(Error::ApiError(ApiErrorCodes::InternalServerError))
+            }
+            status if status.is_client_error() => {
+                Err(Error::ApiError(ApiErrorCodes::HTTPException))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Builder for configuring and creating a `Client` instance.
+///
+/// The `ClientBuilder` provides a fluent interface for setting optional parameters,
+/// allowing customization of `project_id`, `version_id`, and `base_url`. Once all
+/// desired parameters are set, call `build` to create a `Client` instance.
+#[cfg(feature = "client")]
+pub struct ClientBuilder {
+    api_key: String,
+    project_id: Option<u64>,
+    version_id: Option<String>,
+    base_url: String,
+    on_stream_progress: Option<StreamProgressCallback>,
+    on_metrics: Option<MetricsCallback>,
+    strict_error_bodies: bool,
+    capture_raw_events: bool,
+    event_names: EventNames,
+    redact_content: bool,
+    max_retries: u32,
+    retry_base_delay: Duration,
+    default_version_fallback: String,
+    slow_consumer_policy: SlowConsumerPolicy,
+    stream_buffer: usize,
+    stream_idle_timeout: Option<Duration>,
+    max_request_bytes: Option<usize>,
+    workspace_id: Option<String>,
+    redirect_policy: Option<Policy>,
+    http_client: Option<ReqwestClient>,
+    headers: HeaderMap,
+    invalid_header: Option<String>,
+}
+
+#[cfg(feature = "client")]
+impl ClientBuilder {
+    /// Sets the `project_id` for the `Client`.
+    ///
+    /// This `project_id` is used as the default project for API requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_id` - The ID of the project.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .project_id(123);
+    /// ```
+    pub fn project_id(mut self, project_id: u64) -> Self {
+        self.project_id = Some(project_id);
+        self
+    }
+
+    /// Sets the `version_id` for the `Client`.
+    ///
+    /// This `version_id` represents the version of the project or document and
+    /// will be used in API requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `version_id` - The UUID of the version.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .version_id("version-uuid".to_string());
+    /// ```
+    pub fn version_id(mut self, version_id: String) -> Self {
+        self.version_id = Some(version_id);
+        self
+    }
+
+    /// Sets a custom `base_url` for the API endpoint.
+    ///
+    /// This is useful if the API endpoint changes or if using a mock server
+    /// for testing purposes.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The base URL of the API as a string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .base_url("https://custom.url/api".to_string());
+    /// ```
+    pub fn base_url(mut self, base_url: String) -> Self {
+        self.base_url = base_url;
+        self
+    }
+
+    /// Sets a callback invoked with the reported token usage during streaming.
+    ///
+    /// The callback fires for every streamed event that carries usage (such as
+    /// `step-finish`), which allows live cost meters for long generations.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback receiving the reported `Usage`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use latitude_sdk::Client;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .on_stream_progress(Arc::new(|usage| println!("{:?} tokens", usage.total_tokens)));
+    /// ```
+    pub fn on_stream_progress(mut self, callback: StreamProgressCallback) -> Self {
+        self.on_stream_progress = Some(callback);
+        self
+    }
+
+    /// Sets a callback invoked with the timing breakdown of every successful request.
+    ///
+    /// The callback fires once the response is fully received, i.e. when a streamed
+    /// response ends, with the time to the response headers, to the first streamed event
+    /// and to completion. This helps tell a slow connection from a slow generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback receiving the request's `Timing`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use latitude_sdk::Client;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .on_metrics(Arc::new(|timing| println!("First event after {:?}", timing.first_event)));
+    /// ```
+    pub fn on_metrics(mut self, callback: MetricsCallback) -> Self {
+        self.on_metrics = Some(callback);
+        self
+    }
+
+    /// Treats successful responses carrying an `error` body as errors.
+    ///
+    /// Some gateways return `200 OK` with an `{ "error": {...} }` body for provider-level
+    /// failures. When enabled, such bodies on the JSON run path are returned as
+    /// `Error::RunError` (or `Error::Other` for unknown codes) instead of a `Response::Json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `strict` - Whether to check successful bodies for an `error` object.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .strict_error_bodies(true);
+    /// ```
+    pub fn strict_error_bodies(mut self, strict: bool) -> Self {
+        self.strict_error_bodies = strict;
+        self
+    }
+
+    /// Keeps the raw `data:` payload of every streamed event.
+    ///
+    /// When enabled, `EventStream::raw` returns the original payload of the last received
+    /// event alongside the parsed `Event`, which helps diagnose parsing problems.
+    ///
+    /// # Arguments
+    ///
+    /// * `capture` - Whether to capture the raw payloads.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .capture_raw_events(true);
+    /// ```
+    pub fn capture_raw_events(mut self, capture: bool) -> Self {
+        self.capture_raw_events = capture;
+        self
+    }
+
+    /// Overrides the SSE event names of streamed Latitude and provider events.
+    ///
+    /// The API names them `latitude-event` and `provider-event`; a proxy renaming them
+    /// would otherwise make every event an `Event::UnknownEvent`.
+    ///
+    /// # Arguments
+    ///
+    /// * `latitude` - The event name carrying Latitude events.
+    /// * `provider` - The event name carrying provider events.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .event_names("lat", "llm");
+    /// ```
+    pub fn event_names(mut self, latitude: &str, provider: &str) -> Self {
+        self.event_names = EventNames {
+            latitude: latitude.to_owned(),
+            provider: provider.to_owned(),
+        };
+        self
+    }
+
+    /// Redacts message content in tracing output.
+    ///
+    /// When enabled, the content of messages, responses and parameters is logged as a
+    /// placeholder carrying only its length, e.g. `<redacted:142>`, while structural
+    /// fields such as roles and paths are kept. This keeps PII out of the logs.
+    ///
+    /// # Arguments
+    ///
+    /// * `redact` - Whether to redact the content.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .redact_content(true);
+    /// ```
+    pub fn redact_content(mut self, redact: bool) -> Self {
+        self.redact_content = redact;
+        self
+    }
+
+    /// Sets how often a rate-limited or failed request is retried.
+    ///
+    /// Requests answered with `429 Too Many Requests` or a server error are retried up to
+    /// `max_retries` times, with an exponential backoff starting at `retry_base_delay`, or
+    /// after the delay of the response's `Retry-After` header. A `Retry-After` longer than
+    /// 30 seconds isn't waited for; the request fails right away with
+    /// `Error::RateLimited` carrying the delay. Streamed requests are only
+    /// retried until the stream starts. A call can override this with
+    /// `Options::max_retries`. Defaults to `0`, i.e. no retries.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_retries` - The number of retries after the first attempt.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .max_retries(3);
+    /// ```
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the delay before the first retry of a request; see `max_retries`.
+    ///
+    /// Every further retry doubles the delay, which is then jittered so that clients
+    /// failing together don't retry in lockstep. Defaults to 250 milliseconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - The base delay of the backoff.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    /// use std::time::Duration;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .max_retries(3)
+    ///     .retry_base_delay(Duration::from_secs(1));
+    /// ```
+    pub fn retry_base_delay(mut self, delay: Duration) -> Self {
+        self.retry_base_delay = delay;
+        self
+    }
+
+    /// Sets the version used when neither a call's options nor `version_id` set one.
+    ///
+    /// This lets production default to a pinned version while development keeps
+    /// following the live version. Defaults to `"live"`.
+    ///
+    /// # Arguments
+    ///
+    /// * `version_id` - The UUID of the fallback version, or `"live"`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .default_version_fallback("version-uuid".to_string());
+    /// ```
+    pub fn default_version_fallback(mut self, version_id: String) -> Self {
+        self.default_version_fallback = version_id;
+        self
+    }
+
+    /// Sets what happens when the consumer of a streamed response falls behind.
+    ///
+    /// By default the stream waits for the consumer (`SlowConsumerPolicy::Block`), which
+    /// also pauses reading the response; a long stall may make the server time out.
+    /// `DropOldest` keeps reading and drops the oldest unreceived events instead, while
+    /// `Error` ends the stream early.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The `SlowConsumerPolicy` to apply.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::{models::response::SlowConsumerPolicy, Client};
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .slow_consumer_policy(SlowConsumerPolicy::DropOldest);
+    /// ```
+    pub fn slow_consumer_policy(mut self, policy: SlowConsumerPolicy) -> Self {
+        self.slow_consumer_policy = policy;
+        self
+    }
+
+    /// Sets how many events of a stream are buffered until its consumer receives them.
+    ///
+    /// Once the buffer is full, the `slow_consumer_policy` applies. A larger buffer
+    /// absorbs bursts of deltas, a smaller one holds less memory per stream. Defaults
+    /// to 100 events; `build` rejects a zero buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The number of buffered events.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into()).stream_buffer(1000);
+    /// ```
+    pub fn stream_buffer(mut self, capacity: usize) -> Self {
+        self.stream_buffer = capacity;
+        self
+    }
+
+    /// Closes streams that receive nothing for the given duration.
+    ///
+    /// A stream whose server stopped sending may stay open indefinitely. With a timeout,
+    /// any frame, including keep-alive comments, resets the timer, and a stream staying
+    /// silent longer ends with `Error::Other("stream idle timeout")`. Disabled by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long a stream may stay silent.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    /// use std::time::Duration;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .stream_idle_timeout(Duration::from_secs(60));
+    /// ```
+    pub fn stream_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.stream_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Rejects requests whose body is larger than the given size before sending them.
+    ///
+    /// Large parameters or chat histories are otherwise only rejected by the server once
+    /// uploaded. With a limit, `run`, `chat` and `log` fail early with an
+    /// `Error::ConfigError` naming the approximate size of the body. Unlimited by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_bytes` - The largest request body, in bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into()).max_request_bytes(1_000_000);
+    /// ```
+    pub fn max_request_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_request_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Sets the workspace requests are scoped to, for accounts with several workspaces.
+    ///
+    /// The workspace is sent in the `X-Workspace-Id` header of every request, unless a
+    /// call overrides it with `Options::workspace_id`.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - The ID of the workspace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .workspace_id("workspace-id".to_string());
+    /// ```
+    pub fn workspace_id(mut self, workspace_id: String) -> Self {
+        self.workspace_id = Some(workspace_id);
+        self
+    }
+
+    /// Sets the policy for following HTTP redirects.
+    ///
+    /// By default, only redirects within the same origin are followed. Following
+    /// redirects silently changes the effective host, and while the HTTP client strips
+    /// the `Authorization` header when a redirect crosses hosts, a permissive policy
+    /// still sends the request body to wherever the API redirects it.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The redirect policy, e.g. `Policy::none()` to never follow redirects.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    /// use reqwest::redirect::Policy;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .redirect_policy(Policy::none());
+    /// ```
+    pub fn redirect_policy(mut self, policy: Policy) -> Self {
+        self.redirect_policy = Some(policy);
+        self
+    }
+
+    /// Sets the HTTP client used to send requests, instead of building a new one.
+    ///
+    /// This allows sharing a connection pool with the rest of an application, or
+    /// configuring proxies and TLS. The `Authorization` header is still added to every
+    /// request, but the client's own settings apply otherwise: `redirect_policy` is
+    /// ignored, and no `User-Agent` is set unless the client sets one.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The preconfigured `reqwest::Client`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    /// use std::time::Duration;
+    ///
+    /// let http_client = reqwest::Client::builder()
+    ///     .connect_timeout(Duration::from_secs(5))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .http_client(http_client);
+    /// ```
+    pub fn http_client(mut self, client: ReqwestClient) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Adds a header sent with every request, e.g. one required by a gateway.
+    ///
+    /// An invalid header name or value makes `build` fail with `Error::ConfigError`.
+    /// Headers can't be combined with a custom `http_client`; set them as its default
+    /// headers instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the header.
+    /// * `value` - The value of the header.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    ///
+    /// let client_builder = Client::builder("your_api_key".into())
+    ///     .header("x-org-id", "acme");
+    /// ```
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        match (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            (Ok(name), Ok(value)) => {
+                self.headers.insert(name, value);
+            }
+            _ => {
+                self.invalid_header
+                    .get_or_insert_with(|| format!("Invalid header: {}", name));
+            }
+        }
+        self
+    }
+
+    /// Adds headers sent with every request; see `header`.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers to add, replacing earlier ones of the same name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::Client;
+    /// use reqwest::header::{HeaderMap, HeaderValue};
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("x-org-id", HeaderValue::from_static("acme"));
+    ///
+    /// let client_builder = Client::builder("your_api_key".into()).headers(headers);
+    /// ```
+    pub fn headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Builds and returns a new `Client` instance.
+    ///
+    /// After setting the necessary parameters, call `build` to create the `Client`.
+    /// Once built, the `Client` can be used to interact with the Latitude API.
+    ///
+    /// # Returns
+    ///
+    /// The `Client`, or `Error::ConfigError` if the API key or a header is invalid, if
+    /// headers were added along with a custom `http_client`, or if the `stream_buffer`
+    /// is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::{error::Error, Client};
+    ///
+    /// let client = Client::builder("your_api_key".into())
+    ///     .project_id(123)
+    ///     .version_id("version-uuid".to_string())
+    ///     .base_url("https://custom.url/api".to_string())
+    ///     .build()
+    ///     .expect("Invalid client configuration");
+    ///
+    /// let result = Client::builder("your_api_key".into())
+    ///     .header("x-org-id", "line\nbreak")
+    ///     .build();
+    ///
+    /// assert!(matches!(result, Err(Error::ConfigError(_))));
+    /// ```
+    pub fn build(self) -> Result<Client, Error> {
+        if let Some(error) = self.invalid_header {
+            return Err(Error::ConfigError(error));
+        }
+
+        if self.stream_buffer == 0 {
+            return Err(Error::ConfigError(
+                "The stream buffer must hold at least one event".to_owned(),
+            ));
+        }
+
+        let http_client = match self.http_client {
+            Some(_) if !self.headers.is_empty() => {
+                return Err(Error::ConfigError(
+                    "Headers can't be added to a custom HTTP client".to_owned(),
+                ))
+            }
+            Some(http_client) => http_client,
+            None => Client::http_client(
+                self.redirect_policy
+                    .unwrap_or_else(Client::same_origin_redirects),
+                self.headers,
+            ),
+        };
+
+        let mut client = Client::with_http_client(
+            self.api_key,
+            self.project_id,
+            self.version_id,
+            Some(self.base_url),
+            http_client,
+        )?;
+        client.on_stream_progress = self.on_stream_progress;
+        client.on_metrics = self.on_metrics;
+        client.strict_error_bodies = self.strict_error_bodies;
+        client.capture_raw_events = self.capture_raw_events;
+        client.event_names = self.event_names;
+        client.redact_content = self.redact_content;
+        client.max_retries = self.max_retries;
+        client.retry_base_delay = self.retry_base_delay;
+        client.default_version_fallback = self.default_version_fallback;
+        client.slow_consumer_policy = self.slow_consumer_policy;
+        client.stream_buffer = self.stream_buffer;
+        client.stream_idle_timeout = self.stream_idle_timeout;
+        client.max_request_bytes = self.max_request_bytes;
+        client.workspace_id = self.workspace_id;
+        Ok(client)
+    }
+}
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use error::ApiResponseCode;
+    use httpmock::Method::POST;
+    use httpmock::Mock;
+    use httpmock::MockServer;
+    use models::document::DocumentParameter;
+    use models::event::{ChainStep, Config, LatitudeEventType, ProviderEventType, TextDelta};
+    use models::event::{Message, ProviderEvent};
+    use models::message::Message as MessageMessage;
+    use models::message::Role;
+    use models::options::Options;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    // Helper function to setup a client for testing
+    fn setup_client(
+        api_key: &str,
+        project_id: Option<u64>,
+        version_id: Option<&str>,
+        base_url: Option<&str>,
+    ) -> Client {
+        let mut client_builder = Client::builder(api_key.into());
+        if let Some(pid) = project_id {
+            client_builder = client_builder.project_id(pid);
+        }
+        if let Some(vid) = version_id {
+            client_builder = client_builder.version_id(vid.to_string());
+        }
+        if let Some(base_url) = base_url {
+            client_builder = client_builder.base_url(base_url.to_string());
+        }
+
+        client_builder.build().unwrap()
+    }
+
+    fn check_standard_result(result: Result<Response, Error>) {
+        match result {
+            Ok(Response::Json(response)) => {
+                assert_eq!(response.uuid, "123e4567-e89b-12d3-a456-426614174000");
+                assert_eq!(response.response.text, "Test response");
+                assert_eq!(response.response.usage.prompt_tokens, Some(10));
+                assert_eq!(response.response.usage.completion_tokens, Some(20));
+                assert_eq!(response.response.usage.total_tokens, Some(30));
+            }
+            Ok(other) => {
+                panic!(
+                    "Expected JSON response but received a different result: {:?}",
+                    other
+                );
+            }
+            Err(e) => {
+                panic!("Error occurred while running document: {:?}", e);
+            }
+        }
+    }
+
+    // Helper function to setup a Mock with a standard JSON response
+    async fn setup_standard_mock(server: &MockServer) -> Mock<'_> {
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run")
+                .header("authorization", "Bearer test_api_key")
+                .header("content-type", "application/json");
+            then.status(200).json_body(json!({
+                "uuid": "123e4567-e89b-12d3-a456-426614174000",
+                "response": {
+                    "text": "Test response",
+                    "usage": {
+                        "prompt_tokens": 10,
+                        "completion_tokens": 20,
+                        "total_tokens": 30
+                    }
+                }
+            }));
+        })
+    }
+
+    /// Helper function to set up a streaming mock event
+    async fn setup_mock_with_stream_event<'a>(
+        server: &'a MockServer,
+        event_name: &'a str,
+        event_data: &'a str,
+    ) -> Mock<'a> {
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run")
+                .header("authorization", "Bearer test_api_key")
+                .header("content-type", "application/json");
+            then.status(200)
+                .body(format!("event: {}\ndata: {}\n\n", event_name, event_data));
+        })
+    }
+
+    #[tokio::test]
+    async fn test_client_creation_with_builder() {
+        let client = setup_client(
+            "test_api_key",
+            Some(12345),
+            Some("test-version"),
+            Some("https://test.url/api"),
+        );
+
+        assert_eq!(client.api_key, "test_api_key");
+        assert_eq!(client.project_id, Some(12345));
+        assert_eq!(client.version_id, Some("test-version".to_string()));
+        assert_eq!(client.base_url, "https://test.url/api");
+    }
+
+    #[tokio::test]
+    async fn test_client_creation_with_default_base_url() {
+        let client = setup_client("test_api_key", Some(12345), Some("test-version"), None);
+
+        assert_eq!(client.api_key, "test_api_key");
+        assert_eq!(client.project_id, Some(12345));
+        assert_eq!(client.version_id, Some("test-version".to_string()));
+        assert_eq!(client.base_url, "https://gateway.latitude.so/api/v2");
+    }
+
+    #[tokio::test]
+    async fn test_client_creation_new_with_default_base_url() {
+        let client = Client::new(
+            "test_api_key".into(),
+            Some(12345),
+            Some("test-version".into()),
+            None,
+        );
+
+        assert_eq!(client.api_key, "test_api_key");
+        assert_eq!(client.project_id, Some(12345));
+        assert_eq!(client.version_id, Some("test-version".to_string()));
+        assert_eq!(client.base_url, "https://gateway.latitude.so/api/v2");
+    }
+
+    #[test]
+    fn test_client_from_config_file() {
+        use std::io::Write;
+
+        let mut file = tempfile::Builder::new()
+            .suffix(".latituderc")
+            .tempfile()
+            .expect("Failed to create config file");
+        writeln!(
+            file,
+            "api_key = \"file_api_key\"\nproject_id = 42\nversion_id = \"file-version\""
+        )
+        .expect("Failed to write config file");
+
+        let env = EnvGuard::set(&[]);
+        let client = Client::from_config_file(file.path());
+        drop(env);
+
+        let client = client.expect("Failed to load config");
+        assert_eq!(client.api_key, "file_api_key");
+        assert_eq!(client.project_id, Some(42));
+        assert_eq!(client.version_id, Some("file-version".to_owned()));
+        assert_eq!(client.base_url, BASE_URL);
+
+        let env = EnvGuard::set(&[(config::VERSION_ID_ENV, "env-version")]);
+        let client = Client::from_config_file(file.path());
+        drop(env);
+
+        let client = client.expect("Failed to load config");
+        assert_eq!(client.project_id, Some(42));
+        assert_eq!(client.version_id, Some("env-version".to_owned()));
+    }
+
+    /// Sets environment variables until dropped, serializing the tests that do so.
+    struct EnvGuard {
+        names: Vec<&'static str>,
+        _lock: std::sync::MutexGuard<'static, ()>,
+    }
+
+    impl EnvGuard {
+        fn set(vars: &[(&'static str, &str)]) -> Self {
+            static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+            let lock = LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            for name in [
+                config::API_KEY_ENV,
+                config::PROJECT_ID_ENV,
+                config::VERSION_ID_ENV,
+                config::BASE_URL_ENV,
+            ] {
+                std::env::remove_var(name);
+            }
+            for (name, value) in vars {
+                std::env::set_var(name, value);
+            }
+
+            Self {
+                names: vars.iter().map(|(name, _)| *name).collect(),
+                _lock: lock,
+            }
+        }
+    }
+
+    impl Drop for EnvGuard {
+        fn drop(&mut self) {
+            for name in &self.names {
+                std::env::remove_var(name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_client_from_env() {
+        let env = EnvGuard::set(&[
+            (config::API_KEY_ENV, "env_api_key"),
+            (config::PROJECT_ID_ENV, "42"),
+            (config::VERSION_ID_ENV, "env-version"),
+            (config::BASE_URL_ENV, "https://custom.url/api"),
+        ]);
+        let client = Client::from_env().expect("Failed to read the environment");
+        drop(env);
+
+        assert_eq!(client.api_key, "env_api_key");
+        assert_eq!(client.project_id, Some(42));
+        assert_eq!(client.version_id, Some("env-version".to_owned()));
+        assert_eq!(client.base_url, "https://custom.url/api");
+
+        let env = EnvGuard::set(&[(config::API_KEY_ENV, "env_api_key")]);
+        let client = Client::from_env().expect("Failed to read the environment");
+        drop(env);
+
+        assert_eq!(client.project_id, None);
+        assert_eq!(client.version_id, None);
+        assert_eq!(client.base_url, BASE_URL);
+
+        let env = EnvGuard::set(&[(config::PROJECT_ID_ENV, "42")]);
+        let missing_key = Client::from_env();
+        drop(env);
+
+        assert!(matches!(missing_key, Err(Error::ConfigError(_))));
+
+        let env = EnvGuard::set(&[
+            (config::API_KEY_ENV, "env_api_key"),
+            (config::PROJECT_ID_ENV, "forty-two"),
+        ]);
+        let invalid_project = Client::from_env();
+        drop(env);
+
+        assert!(matches!(invalid_project, Err(Error::ConfigError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_cross_origin_redirect() {
+        let target = MockServer::start_async().await;
+        let authorized_mock = target.mock(|when, then| {
+            when.method("GET").header_exists("authorization");
+            then.status(404);
+        });
+        let target_mock = target.mock(|when, then| {
+            when.method("GET");
+            then.status(404);
+        });
+
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method("GET");
+            then.status(302)
+                .header("location", target.url("/redirected"));
+        });
+
+        let client = setup_client("test_api_key", Some(12345), None, Some(&server.base_url()));
+        let _ = client.get("test-path", None).await;
+        assert_eq!(target_mock.hits(), 0);
+
+        let client = Client::builder("test_api_key".to_string())
+            .project_id(12345)
+            .base_url(server.base_url())
+            .redirect_policy(Policy::limited(10))
+            .build()
+            .unwrap();
+        let _ = client.get("test-path", None).await;
+        assert_eq!(target_mock.hits(), 1);
+        assert_eq!(authorized_mock.hits(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_custom_http_client_authenticates() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method("GET")
+                .path("/projects/12345/versions/live/documents/test-path")
+                .header("authorization", "Bearer test_api_key")
+                .header("x-app", "tuned");
+            then.status(404);
+        });
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-app", HeaderValue::from_static("tuned"));
+        let http_client = ReqwestClient::builder()
+            .default_headers(headers)
+            .build()
+            .unwrap();
+
+        let client = Client::builder("test_api_key".to_string())
+            .project_id(12345)
+            .base_url(server.base_url())
+            .http_client(http_client)
+            .build()
+            .unwrap();
+
+        let _ = client.get("test-path", None).await;
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_custom_headers_sent() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run")
+                .header("x-org-id", "acme")
+                .header("traceparent", "00-trace-span-01");
+            then.status(200).json_body(json!({
+                "uuid": "conversation-uuid",
+                "response": {
+                    "text": "Hello",
+                    "usage": {
+                        "prompt_tokens": 1,
+                        "completion_tokens": 2,
+                        "total_tokens": 3
+                    }
+                }
+            }));
+        });
+
+        let mut headers = HeaderMap::new();
+        headers.insert("traceparent", HeaderValue::from_static("00-trace-span-01"));
+
+        let client = Client::builder("test_api_key".to_string())
+            .project_id(12345)
+            .base_url(server.base_url())
+            .header("x-org-id", "acme")
+            .headers(headers)
+            .build()
+            .unwrap();
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_owned())
+            .build();
+
+        assert!(matches!(client.run(document).await, Ok(Response::Json(_))));
+        mock.assert();
+    }
+
+    #[test]
+    fn test_invalid_api_key_is_config_error() {
+        let result = Client::builder("test_api_key\n".to_string()).build();
+        assert!(matches!(result, Err(Error::ConfigError(_))));
+
+        let result = Client::try_new("test_api_key\n".to_string(), None, None, None);
+        assert!(matches!(result, Err(Error::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_invalid_header_is_config_error() {
+        let result = Client::builder("test_api_key".to_string())
+            .header("bad header", "value")
+            .build();
+        assert!(matches!(result, Err(Error::ConfigError(_))));
+
+        let result = Client::builder("test_api_key".to_string())
+            .header("x", "bad\n")
+            .build();
+        assert!(
+            matches!(result, Err(Error::ConfigError(message)) if message == "Invalid header: x")
+        );
+
+        let result = Client::builder("test_api_key".to_string())
+            .header("x-org-id", "acme")
+            .http_client(ReqwestClient::new())
+            .build();
+        assert!(matches!(result, Err(Error::ConfigError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_user_agent_contains_sdk_version() {
+        let server = MockServer::start_async().await;
+        let user_agent = format!("latitude-sdk/{}", Client::sdk_version());
+        let mock = server.mock(|when, then| {
+            when.method("GET")
+                .path("/projects/12345/versions/live/documents/test-path")
+                .header("user-agent", &user_agent);
+            then.status(404);
+        });
+
+        let client = setup_client("test_api_key", Some(12345), None, Some(&server.base_url()));
+        let _ = client.get("test-path", None).await;
+
+        assert_eq!(Client::sdk_version(), env!("CARGO_PKG_VERSION"));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_run_document_json_response() {
+        let server = MockServer::start_async().await;
+        let mock = setup_standard_mock(&server).await;
+
+        let client = setup_client(
+            "test_api_key",
+            Some(12345),
+            Some("live"),
+            Some(&server.base_url()),
+        );
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".into())
+            .build();
+
+        let result = client.run(document).await;
+        check_standard_result(result);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_run_document_with_options() {
+        let server = MockServer::start_async().await;
+        let mock = setup_standard_mock(&server).await;
+
+        let client = setup_client(
+            "test_api_key",
+            Some(12345),
+            Some("live"),
+            Some(&server.base_url()),
+        );
+
+        let options = Options::builder()
+            .project_id(12345)
+            .version_id("live".into())
+            .build();
+
+        #[derive(Serialize, Debug, Default)]
+        struct Params {
+            user_message: String,
+        }
+
+        let parameters = Params {
+            user_message: "Hello, world!".to_owned(),
+        };
+
+        let document = RunDocument::builder()
+            .path("test-path".into())
+            .options(options)
+            .parameters(parameters)
+            .build();
+
+        let result = client.run(document).await;
+        check_standard_result(result);
+        mock.assert();
+    }
+
+    #[cfg(feature = "schema-validation")]
+    async fn setup_json_text_mock<'a>(server: &'a MockServer, text: &'a str) -> Mock<'a> {
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(200).json_body(json!({
+                "uuid": "123e4567-e89b-12d3-a456-426614174000",
+                "response": {
+                    "text": text,
+                    "usage": {
+                        "prompt_tokens": 10,
+                        "completion_tokens": 20,
+                        "total_tokens": 30
+                    }
+                }
+            }));
+        })
+    }
+
+    #[cfg(feature = "schema-validation")]
+    fn emotion_schema() -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "emotion": { "type": "string" },
+                "score": { "type": "number" }
+            },
+            "required": ["emotion", "score"]
+        })
+    }
+
+    #[cfg(feature = "schema-validation")]
+    #[tokio::test]
+    async fn test_run_document_output_schema_conforming() {
+        let server = MockServer::start_async().await;
+        let mock = setup_json_text_mock(&server, r#"{"emotion":"joy","score":0.9}"#).await;
+
+        let client = setup_client("test_api_key", Some(12345), None, Some(&server.base_url()));
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".into())
+            .output_schema(emotion_schema())
+            .build();
+
+        let result = client.run(document).await;
+        assert!(matches!(result, Ok(Response::Json(_))));
+        mock.assert();
+    }
+
+    #[cfg(feature = "schema-validation")]
+    #[tokio::test]
+    async fn test_run_document_output_schema_non_conforming() {
+        let server = MockServer::start_async().await;
+        let mock = setup_json_text_mock(&server, r#"{"emotion":"joy"}"#).await;
+
+        let client = setup_client("test_api_key", Some(12345), None, Some(&server.base_url()));
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".into())
+            .output_schema(emotion_schema())
+            .build();
+
+        let result = client.run(document).await;
+        assert!(matches!(result, Err(Error::ResponseFormatError(msg)) if msg.contains("score")));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_run_document_with_query_params() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run")
+                .query_param("debug", "true")
+                .query_param("flag", "a b&c=d");
+            then.status(200).json_body(json!({
+                "uuid": "123e4567-e89b-12d3-a456-426614174000",
+                "response": {
+                    "text": "Test response",
+                    "usage": {
+                        "prompt_tokens": 10,
+                        "completion_tokens": 20,
+                        "total_tokens": 30
+                    }
+                }
+            }));
+        });
+
+        let client = setup_client("test_api_key", Some(12345), None, Some(&server.base_url()));
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".into())
+            .query_param("debug", "true")
+            .query_param("flag", "a b&c=d")
+            .build();
+
+        let result = client.run(document).await;
+        check_standard_result(result);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_run_document_strict_error_body() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(200).json_body(json!({
+                "error": {
+                    "code": "AIRunError",
+                    "message": "Provider failed to respond"
+                }
+            }));
+        });
+
+        let client = Client::builder("test_api_key".to_string())
+            .project_id(12345)
+            .base_url(server.base_url())
+            .strict_error_bodies(true)
+            .build()
+            .unwrap();
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".into())
+            .build();
+
+        let result = client.run(document).await;
+        assert!(matches!(
+            result,
+            Err(Error::RunError(RunErrorCodes::AIRunError))
+        ));
+        mock.assert();
+    }
+
+    #[test]
+    fn test_check_error_body() {
+        assert!(Client::check_error_body(&json!({ "uuid": "123" })).is_ok());
+        assert!(matches!(
+            Client::check_error_body(&json!({ "error": { "message": "Quota exceeded" } })),
+            Err(Error::Other(msg)) if msg == "Quota exceeded"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_run_document_no_project_id() {
+        let server = MockServer::start_async().await;
+        let client = setup_client("test_api_key", None, Some("live"), Some(&server.base_url()));
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".into())
+            .build();
+
+        let result = client.run(document).await;
+
+        // Expect an error due to missing project ID
+        assert!(
+            matches!(result, Err(Error::ConfigError(msg)) if msg.contains("Project ID is required"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_document_no_version_id() {
+        let server = MockServer::start_async().await;
+        let mock = setup_standard_mock(&server).await;
+
+        let client = setup_client("test_api_key", Some(12345), None, Some(&server.base_url()));
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".into())
+            .build();
+
+        let result = client.run(document).await;
+        check_standard_result(result);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_latitude_event_stream() {
+        // Tests `latitude-event` streaming response
+        let server = MockServer::start_async().await;
+        let mock = setup_mock_with_stream_event(
+            &server,
+            "latitude-event",
+            r#"{"type":"chain-step","isLastStep":true,"config":{"provider":"Latitude","model":"gpt-4o-mini"},"messages":[{"role":"system","content":"Generate a joke"}],"uuid":"58e86f35-293c-4f12-a412-9915cb385850"}"#
+        ).await;
+
+        let client = Client::builder("test_api_key".to_string())
+            .project_id(12345)
+            .version_id("live".to_string())
+            .base_url(server.base_url())
+            .build()
+            .unwrap();
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_string())
+            .stream()
+            .build();
+
+        let result = client
+            .run(document)
+            .await
+            .expect("Expected a stream response");
+
+        if let Response::Stream(mut stream) = result {
+            if let Some(Ok(event)) = stream.next().await {
+                match event {
+                    Event::LatitudeEvent(data) => {
+                        assert_eq!(
+                            data.event_type,
+                            LatitudeEventType::ChainStep(ChainStep {
+                                is_last_step: true,
+                                config: Config {
+                                    provider: "Latitude".to_string(),
+                                    model: "gpt-4o-mini".to_string()
+                                },
+                                messages: vec![Message {
+                                    role: Role::System,
+                                    tool_calls: None,
+                                    content: "Generate a joke".to_string()
+                                }],
+                                uuid: Uuid::from_str("58e86f35-293c-4f12-a412-9915cb385850")
+                                    .expect("Failed to parse UUID"),
+                            })
+                        );
+                    }
+                    _ => panic!("Expected LatitudeEvent"),
+                }
+            } else {
+                panic!("Expected an event in the stream");
+            }
+        } else {
+            panic!("Expected stream response");
+        }
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_provider_event_stream() {
+        // Tests `provider-event` streaming response
+        let server = MockServer::start_async().await;
+
+        let mock = setup_mock_with_stream_event(
+            &server,
+            "provider-event",
+            r#"{"type":"text-delta","textDelta": "running"}"#,
+        )
+        .await;
+
+        let client = Client::builder("test_api_key".to_string())
+            .project_id(12345)
+            .version_id("live".to_string())
+            .base_url(server.base_url())
+            .build()
+            .unwrap();
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_string())
+            .stream()
+            .build();
+
+        let result = client
+            .run(document)
+            .await
+            .expect("Expected a stream response");
+
+        if let Response::Stream(mut stream) = result {
+            if let Some(Ok(event)) = stream.next().await {
+                match event {
+                    Event::ProviderEvent(data) => {
+                        assert_eq!(
+                            data.event_type,
+                            ProviderEventType::TextDelta(TextDelta {
+                                text_delta: "running".to_string(),
+                            })
+                        );
+                    }
+                    _ => panic!("Expected ProviderEvent"),
+                }
+            } else {
+                panic!("Expected an event in the stream");
+            }
+        } else {
+            panic!("Expected stream response");
+        }
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_run_tee() {
+        let server = MockServer::start_async().await;
+        let mock = test_util::mock_run_stream(
+            &server,
+            "test-path",
+            &[
+                (
+                    "latitude-event",
+                    r#"{"type":"chain-step","isLastStep":true,"config":{"provider":"Latitude","model":"gpt-4o-mini"},"messages":[{"role":"system","content":"Greet"}],"uuid":"58e86f35-293c-4f12-a412-9915cb385850"}"#,
+                ),
+                (
+                    "provider-event",
+                    r#"{"type":"text-delta","textDelta":"Hello"}"#,
+                ),
+                (
+                    "provider-event",
+                    r#"{"type":"text-delta","textDelta":" world"}"#,
+                ),
+                (
+                    "latitude-event",
+                    r#"{"type":"chain-complete","config":{"provider":"Latitude","model":"gpt-4o-mini"},"messages":[],"response":{"streamType":"text","documentLogUuid":"58e86f35-293c-4f12-a412-9915cb385850","text":"Hello world","toolCalls":[],"usage":{"promptTokens":4,"completionTokens":2,"totalTokens":6}}}"#,
+                ),
+            ],
+        );
+
+        let client = test_util::client(&server);
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_string())
+            .build();
+
+        let (mut events, final_response) = client
+            .run_tee(document)
+            .await
+            .expect("Failed to run document");
+
+        let mut live_text = String::new();
+        let mut event_count = 0;
+        while let Some(Ok(event)) = events.next().await {
+            event_count += 1;
+            if let Event::ProviderEvent(event) = event {
+                if let ProviderEventType::TextDelta(delta) = event.event_type {
+                    live_text.push_str(&delta.text_delta);
+                }
+            }
+        }
+
+        let final_response = final_response.await.expect("Expected a final response");
+
+        assert_eq!(event_count, 4);
+        assert_eq!(live_text, "Hello world");
+        assert_eq!(final_response.uuid, "58e86f35-293c-4f12-a412-9915cb385850");
+        assert_eq!(final_response.response.text, "Hello world");
+        assert_eq!(final_response.response.usage.total_tokens, Some(6));
+        mock.assert();
+    }
+
+    #[test]
+    fn test_stream_without_runtime() {
+        let client = setup_client("test_api_key", Some(12345), None, Some("http://localhost"));
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_string())
+            .stream()
+            .build();
+
+        let result = futures_lite::future::block_on(client.run(document));
+
+        assert!(
+            matches!(result, Err(Error::ConfigError(message)) if message == "no tokio runtime")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_capture_raw_events() {
+        let server = MockServer::start_async().await;
+        let data = r#"{"type":"text-delta","textDelta": "running"}"#;
+        let mock = setup_mock_with_stream_event(&server, "provider-event", data).await;
+
+        let client = Client::builder("test_api_key".to_string())
+            .project_id(12345)
+            .base_url(server.base_url())
+            .capture_raw_events(true)
+            .build()
+            .unwrap();
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_string())
+            .stream()
+            .build();
+
+        match client.run(document).await {
+            Ok(Response::Stream(mut stream)) => {
+                assert!(stream.raw().is_none());
+                assert!(stream.next().await.is_some());
+                assert_eq!(stream.raw(), Some(data));
+            }
+            _ => panic!("Expected stream response"),
+        }
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_run_split() {
+        let server = MockServer::start_async().await;
+        let body = [
+            r#"{"type":"text-delta","textDelta":"Let me check"}"#,
+            r#"{"type":"tool-call","toolCallId":"call-1","toolName":"get_weather","args":{"city":"Berlin"}}"#,
+            r#"{"type":"text-delta","textDelta":" the weather"}"#,
+            r#"{"type":"tool-call-delta","toolCallId":"call-2","toolName":"get_time","argsTextDelta":"{}"}"#,
+            r#"{"type":"step-finish","finishReason":"tool-calls","usage":{"promptTokens":10,"completionTokens":5,"totalTokens":15},"response":{"id":"resp-1","timestamp":"2024-11-01T00:00:00Z","modelId":"gpt-4o-mini"},"isContinued":true}"#,
+        ]
+        .iter()
+        .map(|data| format!("event: provider-event\ndata: {}\n\n", data))
+        .collect::<String>();
+
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(200)
+                .header("content-type", "text/event-stream")
+                .body(body);
+        });
+
+        let client = setup_client("test_api_key", Some(12345), None, Some(&server.base_url()));
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_string())
+            .build();
+
+        let (mut text, mut tool_calls) = client
+            .run_split(document)
+            .await
+            .expect("Failed to run document");
+
+        let mut deltas = vec![];
+        while let Some(delta) = text.recv().await {
+            deltas.push(delta);
+        }
+
+        let mut tool_call_names = vec![];
+        while let Some(tool_call) = tool_calls.recv().await {
+            tool_call_names.push(tool_call.tool_name);
+        }
+
+        assert_eq!(deltas, vec!["Let me check", " the weather"]);
+        assert_eq!(tool_call_names, vec!["get_weather", "get_time"]);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_run_split_drained_sequentially() {
+        let server = MockServer::start_async().await;
+        let tool_calls: Vec<String> = (0..5)
+            .map(|i| {
+                json!({
+                    "type": "tool-call",
+                    "toolCallId": format!("call-{}", i),
+                    "toolName": format!("tool_{}", i),
+                    "args": {}
+                })
+                .to_string()
+            })
+            .collect();
+        let mut events: Vec<(&str, &str)> = tool_calls
+            .iter()
+            .map(|data| ("provider-event", data.as_str()))
+            .collect();
+        events.push((
+            "provider-event",
+            r#"{"type":"text-delta","textDelta":"Done"}"#,
+        ));
+        test_util::mock_run_stream(&server, "test-path", &events);
+
+        let client = Client::builder(test_util::API_KEY.into())
+            .project_id(test_util::PROJECT_ID)
+            .base_url(server.base_url())
+            .stream_buffer(1)
+            .build()
+            .unwrap();
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_string())
+            .build();
+
+        let (mut text, mut tool_calls) = client
+            .run_split(document)
+            .await
+            .expect("Failed to run document");
+
+        let drain = async {
+            let mut deltas = vec![];
+            while let Some(delta) = text.recv().await {
+                deltas.push(delta);
+            }
+
+            let mut tool_call_names = vec![];
+            while let Some(tool_call) = tool_calls.recv().await {
+                tool_call_names.push(tool_call.tool_name);
+            }
+
+            (deltas, tool_call_names)
+        };
+        let (deltas, tool_call_names) = tokio::time::timeout(Duration::from_secs(5), drain)
+            .await
+            .expect("Draining the channels one after the other stalled");
+
+        assert_eq!(deltas, vec!["Done"]);
+        assert_eq!(
+            tool_call_names,
+            vec!["tool_0", "tool_1", "tool_2", "tool_3", "tool_4"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_keep_alive_comments_are_ignored() {
+        let server = MockServer::start_async().await;
+        let body = [
+            ": keep-alive\n\n",
+            &test_util::sse_body(&[(
+                "provider-event",
+                r#"{"type":"text-delta","textDelta":"Hello"}"#,
+            )]),
+            ": keep-alive\n\n:\n\n",
+            &test_util::sse_body(&[(
+                "provider-event",
+                r#"{"type":"text-delta","textDelta":" world"}"#,
+            )]),
+            ": keep-alive\n\n",
+        ]
+        .concat();
+
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(200)
+                .header("content-type", "text/event-stream")
+                .body(body);
+        });
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_string())
+            .stream()
+            .build();
+
+        let mut deltas = vec![];
+        match test_util::client(&server).run(document).await {
+            Ok(Response::Stream(mut stream)) => {
+                while let Some(Ok(event)) = stream.next().await {
+                    match event {
+                        Event::ProviderEvent(ProviderEvent {
+                            event_type: ProviderEventType::TextDelta(delta),
+                        }) => deltas.push(delta.text_delta),
+                        other => panic!("Expected a text delta, got {:?}", other),
+                    }
+                }
+            }
+            _ => panic!("Expected stream response"),
+        }
+
+        assert_eq!(deltas, vec!["Hello", " world"]);
+        mock.assert();
+    }
+
+    /// Serves a single streamed run response, writing each chunk as a separate body chunk.
+    ///
+    /// Unless `finish` is set, the response is left open after the last chunk, as if the
+    /// server were still generating.
+    async fn serve_chunks(chunks: Vec<Vec<u8>>, finish: bool) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind listener");
+        let address = listener.local_addr().expect("Failed to get address");
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.expect("Failed to accept");
+            let mut request = vec![0; 8192];
+            let _ = socket.read(&mut request).await;
+
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ntransfer-encoding: chunked\r\n\r\n")
+                .await
+                .expect("Failed to write headers");
+
+            for chunk in chunks {
+                let chunk = [format!("{:x}\r\n", chunk.len()).as_bytes(), &chunk, b"\r\n"].concat();
+                socket
+                    .write_all(&chunk)
+                    .await
+                    .expect("Failed to write chunk");
+                socket.flush().await.expect("Failed to flush chunk");
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+
+            if finish {
+                let _ = socket.write_all(b"0\r\n\r\n").await;
+            } else {
+                std::future::pending::<()>().await;
+            }
+        });
+
+        format!("http://{}", address)
+    }
+
+    #[tokio::test]
+    async fn test_clone_with_key() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind listener");
+        let address = listener.local_addr().expect("Failed to get address");
+
+        // Accepts a single connection, so the second request only succeeds if it reuses it.
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.expect("Failed to accept");
+            let body = serde_json::to_string(&Document::default()).unwrap();
+            let mut authorizations = vec![];
+
+            for _ in 0..2 {
+                let mut request = vec![];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let mut byte = [0; 1];
+                    socket.read_exact(&mut byte).await.expect("Failed to read");
+                    request.push(byte[0]);
+                }
+                let request = String::from_utf8(request).unwrap();
+                authorizations.extend(
+                    request
+                        .lines()
+                        .filter(|line| line.to_lowercase().starts_with("authorization:"))
+                        .map(|line| line.split_once(':').unwrap().1.trim().to_owned()),
+                );
+
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket
+                    .write_all(response.as_bytes())
+                    .await
+                    .expect("Failed to write response");
+            }
+
+            authorizations
+        });
+
+        let client = Client::builder("old_api_key".into())
+            .project_id(test_util::PROJECT_ID)
+            .base_url(format!("http://{}", address))
+            .build()
+            .unwrap();
+        let rotated = client
+            .clone_with_key("new_api_key".into())
+            .expect("Failed to rotate key");
+
+        client
+            .get("test-path", None)
+            .await
+            .expect("Failed to get document");
+        rotated
+            .get("test-path", None)
+            .await
+            .expect("Failed to get document");
+
+        assert_eq!(
+            server.await.unwrap(),
+            vec!["Bearer old_api_key", "Bearer new_api_key"]
+        );
+        assert!(matches!(
+            client.clone_with_key("invalid\nkey".into()),
+            Err(Error::ConfigError(_))
+        ));
+    }
+
+    /// A tracing writer collecting the formatted output in memory.
+    #[derive(Clone, Default)]
+    struct TraceBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for TraceBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_redact_content() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/logs");
+            then.status(200)
+                .json_body(serde_json::to_value(LogResponse::default()).unwrap());
+        });
+
+        let buffer = TraceBuffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let client = Client::builder(test_util::API_KEY.into())
+            .project_id(test_util::PROJECT_ID)
+            .base_url(server.base_url())
+            .redact_content(true)
+            .build()
+            .unwrap();
+        let log = Log::builder()
+            .path("test-path")
+            .add_message(
+                MessageMessage::builder()
+                    .role(Role::User)
+                    .add_content("text", "My email is jane@example.com")
+                    .build()
+                    .unwrap(),
+            )
+            .response("Noted")
+            .build()
+            .unwrap();
+
+        client.log(log).await.expect("Failed to log");
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(!output.contains("jane@example.com"));
+        assert!(!output.contains("Noted"));
+        assert!(output.contains("role=user"));
+        assert!(output.contains("content=<redacted:28>"));
+        assert!(output.contains("response=<redacted:5>"));
+        assert!(output.contains("path=test-path"));
+    }
+
+    #[tokio::test]
+    async fn test_per_call_max_retries() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(429);
+        });
+
+        let client = Client::builder(test_util::API_KEY.into())
+            .project_id(test_util::PROJECT_ID)
+            .base_url(server.base_url())
+            .max_retries(2)
+            .build()
+            .unwrap();
+
+        let interactive = RunDocument::<()>::builder()
+            .path("test-path".to_owned())
+            .options(Options::builder().max_retries(0).build())
+            .build();
+        let result = client.run(interactive).await;
+
+        assert!(matches!(result, Err(Error::RateLimited { .. })));
+        assert_eq!(mock.hits(), 1);
+
+        let batch = RunDocument::<()>::builder()
+            .path("test-path".to_owned())
+            .build();
+        let result = client.run(batch).await;
+
+        assert!(result.is_err());
+        assert_eq!(mock.hits(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_retries_with_backoff() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind listener");
+        let address = listener.local_addr().expect("Failed to get address");
+        let hits = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let counter = hits.clone();
+        tokio::spawn(async move {
+            let body = json!({
+                "uuid": "conversation-uuid",
+                "response": {
+                    "text": "Hello after retrying",
+                    "usage": { "prompt_tokens": 1, "completion_tokens": 2, "total_tokens": 3 }
+                }
+            })
+            .to_string();
+            let responses = [
+                "HTTP/1.1 429 Too Many Requests\r\nretry-after: 0\r\ncontent-length: 0\r\nconnection: close\r\n\r\n".to_owned(),
+                "HTTP/1.1 429 Too Many Requests\r\ncontent-length: 0\r\nconnection: close\r\n\r\n".to_owned(),
+                format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                ),
+            ];
+
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.expect("Failed to accept");
+                let mut request = vec![0; 8192];
+                let _ = socket.read(&mut request).await;
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                socket
+                    .write_all(response.as_bytes())
+                    .await
+                    .expect("Failed to write response");
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        let client = Client::builder(test_util::API_KEY.into())
+            .project_id(test_util::PROJECT_ID)
+            .base_url(format!("http://{}", address))
+            .max_retries(2)
+            .retry_base_delay(Duration::from_millis(10))
+            .build()
+            .unwrap();
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_owned())
+            .build();
+        let result = client.run(document).await;
+
+        match result {
+            Ok(Response::Json(response)) => {
+                assert_eq!(response.response.text, "Hello after retrying");
+            }
+            other => panic!("Expected a JSON response, got {:?}", other),
+        }
+        assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_retry_after() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(429).header("retry-after", "30");
+        });
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_owned())
+            .build();
+        let result = test_util::client(&server).run(document).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::RateLimited { retry_after: Some(delay) }) if delay == Duration::from_secs(30)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_long_retry_after_is_not_waited_for() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(429).header("retry-after", "3600");
+        });
+
+        let client = Client::builder(test_util::API_KEY.into())
+            .project_id(test_util::PROJECT_ID)
+            .base_url(server.base_url())
+            .max_retries(3)
+            .build()
+            .unwrap();
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_owned())
+            .build();
+        let result = tokio::time::timeout(Duration::from_secs(5), client.run(document))
+            .await
+            .expect("Waited for the Retry-After delay");
+
+        assert!(matches!(
+            result,
+            Err(Error::RateLimited { retry_after: Some(delay) }) if delay == Duration::from_secs(3600)
+        ));
+        mock.assert_hits(1);
+    }
+
+    #[test]
+    fn test_retry_after_http_date() {
+        let headers = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(RETRY_AFTER, HeaderValue::from_str(value).unwrap());
+            headers
+        };
+
+        let date = (chrono::Utc::now() + chrono::Duration::seconds(60))
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string();
+        let delay = Client::retry_after(&headers(&date)).expect("Missing delay");
+        assert!(delay > Duration::from_secs(55) && delay <= Duration::from_secs(60));
+
+        assert_eq!(
+            Client::retry_after(&headers("Wed, 21 Oct 2015 07:28:00 GMT")),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(Client::retry_after(&headers("soon")), None);
+    }
+
+    #[tokio::test]
+    async fn test_run_text_pending_tool_calls() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(200).json_body(json!({
+                "uuid": "conversation-uuid",
+                "response": {
+                    "streamType": "text",
+                    "text": "",
+                    "toolCalls": [{
+                        "id": "call-1",
+                        "name": "get_weather",
+                        "arguments": { "city": "Zurich" }
+                    }],
+                    "usage": {
+                        "prompt_tokens": 10,
+                        "completion_tokens": 5,
+                        "total_tokens": 15
+                    }
+                }
+            }));
+        });
+
+        let client = test_util::client(&server);
+        let document = || {
+            RunDocument::<()>::builder()
+                .path("test-path".to_owned())
+                .build()
+        };
+
+        match client.run(document()).await {
+            Ok(Response::Json(response)) => {
+                assert!(response.response.awaits_tool_results());
+                assert_eq!(response.response.tool_calls[0].name, "get_weather");
+                assert_eq!(
+                    response.response.tool_calls[0].arguments,
+                    json!({ "city": "Zurich" })
+                );
+            }
+            other => panic!("Expected a JSON response, got {:?}", other),
+        }
+
+        match client.run_text(document()).await {
+            Err(Error::PendingToolCalls(tool_calls)) => {
+                assert_eq!(tool_calls.len(), 1);
+                assert_eq!(tool_calls[0].id, "call-1");
+            }
+            other => panic!("Expected pending tool calls, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_per_call_timeout() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(200)
+                .delay(Duration::from_secs(2))
+                .json_body(json!({
+                    "uuid": "123",
+                    "response": { "text": "late", "usage": {} }
+                }));
+        });
+
+        let client = test_util::client(&server);
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_owned())
+            .options(
+                Options::builder()
+                    .timeout(Duration::from_millis(100))
+                    .build(),
+            )
+            .build();
+        let result = client.run(document).await;
+
+        assert!(matches!(result, Err(Error::HttpError(error)) if error.is_timeout()));
+    }
+
+    #[tokio::test]
+    async fn test_workspace_id_header() {
+        let server = MockServer::start_async().await;
+        let document = |path: &str| {
+            json!({
+                "id": 1,
+                "documentUuid": "doc-uuid",
+                "path": path,
+                "content": "Hello",
+                "resolvedContent": "Hello",
+                "contentHash": "hash",
+                "commitId": 1,
+                "deletedAt": null,
+                "createdAt": "2024-11-01T00:00:00Z",
+                "updatedAt": "2024-11-01T00:00:00Z",
+                "mergedAt": "2024-11-01T00:00:00Z",
+                "projectId": 12345,
+                "config": { "provider": "OpenAI", "model": "gpt-4o-mini" }
+            })
+        };
+        let default_mock = server.mock(|when, then| {
+            when.method("GET")
+                .path("/projects/12345/versions/live/documents/default")
+                .header("x-workspace-id", "workspace-1");
+            then.status(200).json_body(document("default"));
+        });
+        let override_mock = server.mock(|when, then| {
+            when.method("GET")
+                .path("/projects/12345/versions/live/documents/override")
+                .header("x-workspace-id", "workspace-2");
+            then.status(200).json_body(document("override"));
+        });
+
+        let client = Client::builder(test_util::API_KEY.into())
+            .project_id(test_util::PROJECT_ID)
+            .base_url(server.base_url())
+            .workspace_id("workspace-1".to_owned())
+            .build()
+            .unwrap();
+
+        let default = client.get("default", None).await.unwrap();
+        let overridden = client
+            .get(
+                "override",
+                Some(
+                    Options::builder()
+                        .workspace_id("workspace-2".to_owned())
+                        .build(),
+                ),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(default.path, "default");
+        assert_eq!(overridden.path, "override");
+        default_mock.assert();
+        override_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_get_commit() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method("GET").path("/projects/12345/commits/42");
+            then.status(200).json_body(json!({
+                "id": 42,
+                "uuid": "2f1a9c8e-6d0b-4b8e-9a41-7f3c2e1d0b5a",
+                "title": "Tighten the summarizer prompt",
+                "createdAt": "2024-11-01T00:00:00Z"
+            }));
+        });
+
+        let commit = test_util::client(&server)
+            .get_commit(test_util::PROJECT_ID, 42)
+            .await
+            .expect("Failed to get commit");
+
+        assert_eq!(commit.id, 42);
+        assert_eq!(commit.uuid, "2f1a9c8e-6d0b-4b8e-9a41-7f3c2e1d0b5a");
+        assert_eq!(commit.title, "Tighten the summarizer prompt");
+        assert_eq!(commit.created_at, "2024-11-01T00:00:00Z");
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_document_history() {
+        let server = MockServer::start_async().await;
+        let mut mocks = Vec::new();
+        for (commit_id, content) in [(1, "Classify"), (2, "Classify briefly")] {
+            let uuid = format!("commit-uuid-{}", commit_id);
+            mocks.push(server.mock(|when, then| {
+                when.method("GET")
+                    .path(format!("/projects/12345/commits/{}", commit_id));
+                then.status(200).json_body(json!({
+                    "id": commit_id,
+                    "uuid": uuid,
+                    "title": "Update the prompt",
+                    "createdAt": "2024-11-01T00:00:00Z"
+                }));
+            }));
+            mocks.push(server.mock(|when, then| {
+                when.method("GET").path(format!(
+                    "/projects/12345/versions/{}/documents/Workers/EmotionAnalyzer",
+                    uuid
+                ));
+                then.status(200).json_body(json!({
+                    "id": 1,
+                    "documentUuid": "doc-uuid",
+                    "path": "Workers/EmotionAnalyzer",
+                    "content": content,
+                    "resolvedContent": content,
+                    "contentHash": format!("hash-{}", commit_id),
+                    "commitId": commit_id,
+                    "deletedAt": null,
+                    "createdAt": "2024-11-01T00:00:00Z",
+                    "updatedAt": "2024-11-01T00:00:00Z",
+                    "mergedAt": "2024-11-01T00:00:00Z",
+                    "projectId": 12345,
+                    "config": { "provider": "OpenAI", "model": "gpt-4o-mini" }
+                }));
+            }));
+        }
+
+        let history = test_util::client(&server)
+            .document_history("Workers/EmotionAnalyzer", &[1, 2], None)
+            .await
+            .expect("Failed to fetch document history");
+
+        assert_eq!(history.len(), 2);
+        assert_ne!(history[0].commit_id, history[1].commit_id);
+        assert_eq!(history[0].content, "Classify");
+        assert_eq!(history[1].content, "Classify briefly");
+        for mock in mocks {
+            mock.assert();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_version_fallback() {
+        let server = MockServer::start_async().await;
+        let run_mock = test_util::mock_run_json(&server, "test-path", "Hello");
+        let pinned_run_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/pinned-uuid/documents/run");
+            then.status(200).json_body(json!({
+                "uuid": test_util::CONVERSATION_UUID,
+                "response": { "text": "Pinned", "usage": {} }
+            }));
+        });
+        let get_mock = server.mock(|when, then| {
+            when.method("GET")
+                .path("/projects/12345/versions/pinned-uuid/documents/test-path");
+            then.status(200)
+                .json_body(serde_json::to_value(Document::default()).unwrap());
+        });
+
+        let client = Client::builder(test_util::API_KEY.into())
+            .project_id(test_util::PROJECT_ID)
+            .base_url(server.base_url())
+            .default_version_fallback("pinned-uuid".to_owned())
+            .build()
+            .unwrap();
+        assert_eq!(client.default_version_fallback(), "pinned-uuid");
+
+        let response = client
+            .run(
+                RunDocument::<()>::builder()
+                    .path("test-path".to_owned())
+                    .build(),
+            )
+            .await
+            .expect("Failed to run document");
+        assert!(matches!(response, Response::Json(json) if json.response.text == "Pinned"));
+
+        client
+            .get("test-path", None)
+            .await
+            .expect("Failed to get document");
+
+        pinned_run_mock.assert();
+        get_mock.assert();
+        assert_eq!(run_mock.hits(), 0);
+        assert_eq!(
+            test_util::client(&server).default_version_fallback(),
+            "live"
+        );
+    }
+
+    /// Runs a stream of 150 text deltas, numbered from 0, that is only consumed once the
+    /// background task is done with it, returning the received deltas and errors.
+    ///
+    /// Under `SlowConsumerPolicy::Block` the task waits for the consumer instead, so the
+    /// stream is consumed right away.
+    async fn consume_slowly(policy: SlowConsumerPolicy) -> (Vec<String>, Vec<Error>, bool) {
+        let server = MockServer::start_async().await;
+        let deltas = (0..150)
+            .map(|i| format!(r#"{{"type":"text-delta","textDelta":"{}"}}"#, i))
+            .collect::<Vec<_>>();
+        let events = deltas
+            .iter()
+            .map(|delta| ("provider-event", delta.as_str()))
+            .collect::<Vec<_>>();
+        test_util::mock_run_stream(&server, "test-path", &events);
+
+        let client = Client::builder(test_util::API_KEY.into())
+            .project_id(test_util::PROJECT_ID)
+            .base_url(server.base_url())
+            .slow_consumer_policy(policy)
+            .build()
+            .unwrap();
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_owned())
+            .stream()
+            .build();
+
+        let Response::Stream(mut stream) = client.run(document).await.unwrap() else {
+            panic!("Expected a stream");
+        };
+
+        if policy != SlowConsumerPolicy::Block {
+            // Resolves once the task ended, as the stream has no `chain-complete` event.
+            stream.usage_future().await;
+        }
+
+        let mut received = vec![];
+        let mut errors = vec![];
+        while let Some(item) = stream.next().await {
+            match item {
+                Ok(event) => received.extend(event.text_delta().map(str::to_owned)),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        (received, errors, stream.overflowed())
+    }
+
+    #[tokio::test]
+    async fn test_slow_consumer_block() {
+        let (received, errors, overflowed) = consume_slowly(SlowConsumerPolicy::Block).await;
+
+        assert_eq!(
+            received,
+            (0..150).map(|i| i.to_string()).collect::<Vec<_>>()
+        );
+        assert!(errors.is_empty());
+        assert!(!overflowed);
+    }
+
+    #[tokio::test]
+    async fn test_slow_consumer_drop_oldest() {
+        let (received, errors, overflowed) = consume_slowly(SlowConsumerPolicy::DropOldest).await;
+
+        // The oldest deltas were dropped, the rest arrive in order up to the last one.
+        let first = received[0].parse::<usize>().unwrap();
+        assert!(first > 0);
+        assert_eq!(
+            received,
+            (first..150).map(|i| i.to_string()).collect::<Vec<_>>()
+        );
+        assert!(errors.is_empty());
+        assert!(!overflowed);
+    }
+
+    #[tokio::test]
+    async fn test_slow_consumer_drop_oldest_keeps_error_kind() {
+        let server = MockServer::start_async().await;
+        test_util::mock_run_stream(
+            &server,
+            "test-path",
+            &[
+                ("provider-event", "not json"),
+                (
+                    "provider-event",
+                    r#"{"type":"text-delta","textDelta":"Hi"}"#,
+                ),
+            ],
+        );
+
+        let client = Client::builder(test_util::API_KEY.into())
+            .project_id(test_util::PROJECT_ID)
+            .base_url(server.base_url())
+            .slow_consumer_policy(SlowConsumerPolicy::DropOldest)
+            .build()
+            .unwrap();
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_owned())
+            .stream()
+            .build();
+
+        let Response::Stream(mut stream) = client.run(document).await.unwrap() else {
+            panic!("Expected a stream");
+        };
+        stream.usage_future().await;
+
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(Error::SerializationError(_)))
+        ));
+        assert_eq!(
+            stream.next().await.unwrap().unwrap().text_delta(),
+            Some("Hi")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_slow_consumer_error() {
+        let (received, errors, overflowed) = consume_slowly(SlowConsumerPolicy::Error).await;
+
+        assert_eq!(
+            received,
+            (0..100).map(|i| i.to_string()).collect::<Vec<_>>()
+        );
+        assert!(matches!(errors.as_slice(), [Error::Other(_)]));
+        assert!(overflowed);
+    }
+
+    #[tokio::test]
+    async fn test_stream_outlives_client() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(200).body(concat!(
+                "event: provider-event\n",
+                r#"data: {"type":"text-delta","textDelta":"Hi"}"#,
+                "\n\n",
+            ));
+        });
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_owned())
+            .stream()
+            .build();
+        let Ok(Response::Stream(mut stream)) = test_util::client(&server).run(document).await
+        else {
+            panic!("Expected stream response");
+        };
+
+        assert_eq!(
+            stream
+                .next()
+                .await
+                .and_then(|event| event.ok()?.text_delta().map(str::to_owned)),
+            Some("Hi".to_owned())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_collect_stream() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(200).body(concat!(
+                "event: latitude-event\n",
+                r#"data: {"type":"chain-step","isLastStep":true,"config":{"provider":"Latitude","model":"gpt-4o-mini"},"messages":[],"uuid":"58e86f35-293c-4f12-a412-9915cb385850"}"#,
+                "\n\n",
+                "event: provider-event\n",
+                r#"data: {"type":"text-delta","textDelta":"Hello"}"#,
+                "\n\n",
+                // A payload that fails to parse doesn't end the stream, nor the collection.
+                "event: provider-event\n",
+                "data: not json\n\n",
+                "event: provider-event\n",
+                r#"data: {"type":"text-delta","textDelta":", "}"#,
+                "\n\n",
+                "event: provider-event\n",
+                r#"data: {"type":"text-delta","textDelta":"world"}"#,
+                "\n\n",
+                "event: provider-event\n",
+                r#"data: {"type":"finish","finishReason":"stop","usage":{"promptTokens":8,"completionTokens":3,"totalTokens":11},"response":{"id":"resp-1","timestamp":"2024-11-01T00:00:00Z","modelId":"gpt-4o-mini"}}"#,
+                "\n\n",
+            ));
+        });
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_owned())
+            .stream()
+            .build();
+        let response = test_util::client(&server)
+            .run(document)
+            .await
+            .expect("Failed to run document")
+            .collect()
+            .await
+            .expect("Failed to collect the stream");
+
+        assert_eq!(response.uuid, "58e86f35-293c-4f12-a412-9915cb385850");
+        assert_eq!(response.response.text, "Hello, world");
+        assert_eq!(response.response.usage.prompt_tokens, Some(8));
+        assert_eq!(response.response.usage.completion_tokens, Some(3));
+        assert_eq!(response.response.usage.total_tokens, Some(11));
+    }
+
+    #[tokio::test]
+    async fn test_stream_id_exposed() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(200)
+                .header("X-Stream-Id", "stream-7f3c")
+                .body(concat!(
+                    "event: provider-event\n",
+                    r#"data: {"type":"text-delta","textDelta":"Hi"}"#,
+                    "\n\n",
+                ));
+        });
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_owned())
+            .stream()
+            .build();
+        let Ok(Response::Stream(stream)) = test_util::client(&server).run(document).await else {
+            panic!("Expected stream response");
+        };
+
+        assert_eq!(stream.stream_id(), Some("stream-7f3c"));
+    }
+
+    #[tokio::test]
+    async fn test_usage_future_resolves_after_chain_complete() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(200).body(concat!(
+                "event: provider-event\n",
+                r#"data: {"type":"text-delta","textDelta":"Hi"}"#,
+                "\n\n",
+                "event: provider-event\n",
+                r#"data: {"type":"step-finish","finishReason":"stop","usage":{"promptTokens":10,"completionTokens":5,"totalTokens":15},"response":{"id":"resp-1","timestamp":"2024-11-01T00:00:00Z","modelId":"gpt-4o-mini"},"isContinued":false}"#,
+                "\n\n",
+                "event: latitude-event\n",
+                r#"data: {"type":"chain-complete","config":{"provider":"Latitude","model":"gpt-4o-mini"},"messages":[],"response":{"streamType":"text","text":"Hi","usage":{"promptTokens":12,"completionTokens":5,"totalTokens":17}}}"#,
+                "\n\n",
+            ));
+        });
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_owned())
+            .stream()
+            .build();
+        let Ok(Response::Stream(stream)) = test_util::client(&server).run(document).await else {
+            panic!("Expected stream response");
+        };
+
+        // The usage is known without receiving any event.
+        let usage = tokio::time::timeout(Duration::from_secs(5), stream.usage_future())
+            .await
+            .expect("The usage future didn't resolve");
+
+        assert_eq!(
+            usage,
+            Some(Usage {
+                prompt_tokens: Some(12),
+                completion_tokens: Some(5),
+                total_tokens: Some(17),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_aborts_stalled_streams() {
+        let base_url = serve_chunks(
+            vec![test_util::sse_body(&[(
+                "provider-event",
+                r#"{"type":"text-delta","textDelta":"Hello"}"#,
+            )])
+            .into_bytes()],
+            false,
+        )
+        .await;
+
+        let client = setup_client("test_api_key", Some(12345), None, Some(&base_url));
+        let document = || {
+            RunDocument::<()>::builder()
+                .path("test-path".to_string())
+                .stream()
+                .build()
+        };
+
+        let Ok(Response::Stream(mut stream)) = client.run(document()).await else {
+            panic!("Expected stream response");
+        };
+        assert!(stream.next().await.is_some());
+
+        let started = Instant::now();
+        client.shutdown(Duration::from_millis(100)).await;
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(stream.next().await.is_none());
+        assert!(matches!(
+            client.run(document()).await,
+            Err(Error::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_rejects_stream_of_request_in_flight() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind listener");
+        let address = listener.local_addr().expect("Failed to get address");
+        let (received_sender, received) = tokio::sync::oneshot::channel();
+        let (release, release_receiver) = tokio::sync::oneshot::channel::<()>();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.expect("Failed to accept");
+            let mut request = vec![0; 8192];
+            let _ = socket.read(&mut request).await;
+            let _ = received_sender.send(());
+            let _ = release_receiver.await;
+
+            let chunk = test_util::sse_body(&[(
+                "provider-event",
+                r#"{"type":"text-delta","textDelta":"Hello"}"#,
+            )]);
+            let _ = socket
+                .write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ntransfer-encoding: chunked\r\n\r\n{:x}\r\n{}\r\n",
+                        chunk.len(),
+                        chunk
+                    )
+                    .as_bytes(),
+                )
+                .await;
+            std::future::pending::<()>().await;
+        });
+
+        let client = setup_client(
+            "test_api_key",
+            Some(12345),
+            None,
+            Some(&format!("http://{}", address)),
+        );
+        let run = tokio::spawn({
+            let client = client.clone();
+            async move {
+                client
+                    .run(
+                        RunDocument::<()>::builder()
+                            .path("test-path".to_string())
+                            .stream()
+                            .build(),
+                    )
+                    .await
+            }
+        });
+
+        received.await.expect("Failed to receive the request");
+        client.shutdown(Duration::from_millis(100)).await;
+        release.send(()).expect("Failed to release the response");
+
+        assert!(matches!(
+            run.await.expect("Run task panicked"),
+            Err(Error::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_take_text() {
+        let base_url = serve_chunks(
+            vec![
+                test_util::sse_body(&[(
+                    "provider-event",
+                    r#"{"type":"text-delta","textDelta":"Hello"}"#,
+                )])
+                .into_bytes(),
+                test_util::sse_body(&[(
+                    "provider-event",
+                    r#"{"type":"text-delta","textDelta":" wor"}"#,
+                )])
+                .into_bytes(),
+            ],
+            false,
+        )
+        .await;
+
+        let client = setup_client("test_api_key", Some(12345), None, Some(&base_url));
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_string())
+            .stream()
+            .build();
+
+        let Ok(Response::Stream(mut stream)) = client.run(document).await else {
+            panic!("Expected stream response");
+        };
+
+        assert!(stream.next().await.is_some());
+        assert!(stream.next().await.is_some());
+
+        assert_eq!(stream.cancel_and_take_text(), "Hello wor");
+    }
+
+    #[tokio::test]
+    async fn test_abort_stream() {
+        let base_url = serve_chunks(
+            vec![test_util::sse_body(&[
+                (
+                    "provider-event",
+                    r#"{"type":"text-delta","textDelta":"Hello"}"#,
+                ),
+                (
+                    "provider-event",
+                    r#"{"type":"text-delta","textDelta":" world"}"#,
+                ),
+            ])
+            .into_bytes()],
+            false,
+        )
+        .await;
+
+        let client = setup_client("test_api_key", Some(12345), None, Some(&base_url));
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_string())
+            .stream()
+            .build();
+
+        let Ok(Response::Stream(mut stream)) = client.run(document).await else {
+            panic!("Expected stream response");
+        };
+        assert!(stream.next().await.is_some());
+
+        stream.abort();
+
+        let next = tokio::time::timeout(Duration::from_secs(1), stream.next())
+            .await
+            .expect("The aborted stream should end right away");
+        assert!(next.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_with_reason() {
+        let server = MockServer::start_async().await;
+        test_util::mock_run_stream(
+            &server,
+            "test-path",
+            &[
+                (
+                    "latitude-event",
+                    r#"{"type":"chain-step","isLastStep":true,"config":{"provider":"Latitude","model":"gpt-4o-mini"},"messages":[],"uuid":"58e86f35-293c-4f12-a412-9915cb385850"}"#,
+                ),
+                (
+                    "provider-event",
+                    r#"{"type":"text-delta","textDelta":"Hello"}"#,
+                ),
+            ],
+        );
+        let cancel_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/conversations/58e86f35-293c-4f12-a412-9915cb385850/cancel")
+                .json_body(json!({ "reason": "user pressed stop" }));
+            then.status(200).json_body(json!({}));
+        });
+
+        let client = test_util::client(&server);
+        let mut stream = client
+            .prompt("test-path")
+            .send_stream()
+            .await
+            .expect("Expected a stream");
+        assert!(stream.next().await.is_some());
+
+        stream
+            .cancel_with_reason("user pressed stop")
+            .await
+            .expect("Failed to cancel the run");
+
+        cancel_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_event_split_across_chunks() {
+        let body = test_util::sse_body(&[(
+            "provider-event",
+            r#"{"type":"text-delta","textDelta":"Grüße aus Zürich"}"#,
+        )]);
+
+        // Three-byte chunks split the event name, the JSON payload, the terminating blank
+        // line and the multi-byte characters of the text.
+        let chunks = body.as_bytes().chunks(3).map(<[u8]>::to_vec).collect();
+        let base_url = serve_chunks(chunks, true).await;
+
+        let client = setup_client("test_api_key", Some(12345), None, Some(&base_url));
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_string())
+            .stream()
+            .build();
+
+        let Ok(Response::Stream(mut stream)) = client.run(document).await else {
+            panic!("Expected stream response");
+        };
+
+        let mut events = vec![];
+        while let Some(event) = stream.next().await {
+            events.push(event.expect("Failed to receive event"));
+        }
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].text_delta(), Some("Grüße aus Zürich"));
+    }
+
+    #[tokio::test]
+    async fn test_run_batch_from_input_stream() {
+        let server = MockServer::start_async().await;
+        let mocks = ["first", "second", "third"].map(|message| {
+            server.mock(|when, then| {
+                when.method(POST)
+                    .path("/projects/12345/versions/live/documents/run")
+                    .json_body_partial(
+                        json!({ "parameters": { "user_message": message } }).to_string(),
+                    );
+                then.status(200).json_body(json!({
+                    "uuid": "123e4567-e89b-12d3-a456-426614174000",
+                    "response": {
+                        "text": format!("Answer to {}", message),
+                        "usage": {}
+                    }
+                }));
+            })
+        });
+
+        #[derive(Serialize, Debug, Default)]
+        struct Params {
+            user_message: String,
+        }
+
+        let inputs = tokio_stream::iter(["first", "second", "third"]).map(|message| Params {
+            user_message: message.to_owned(),
+        });
+
+        let client = test_util::client(&server);
+        let texts = client
+            .run_batch("test-path", inputs, 2)
+            .map(|result| result.expect("Failed to run document").response.text)
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(
+            texts,
+            vec!["Answer to first", "Answer to second", "Answer to third"]
+        );
+        for mock in mocks {
+            mock.assert();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_request_bytes() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST);
+            then.status(200);
+        });
+
+        #[derive(Serialize, Debug)]
+        struct Params {
+            transcript: String,
         }
-        if let Some(base_url) = base_url {
-            client_builder = client_builder.base_url(base_url.to_string());
+
+        let client = Client::builder(test_util::API_KEY.into())
+            .project_id(test_util::PROJECT_ID)
+            .base_url(server.base_url())
+            .max_request_bytes(1_000_000)
+            .build()
+            .unwrap();
+
+        let document = RunDocument::builder()
+            .path("test-path".to_owned())
+            .parameters(Params {
+                transcript: "a".repeat(1_500_000),
+            })
+            .build();
+
+        match client.run(document).await {
+            Err(Error::ConfigError(message)) => assert_eq!(
+                message,
+                "The request body is about 1.5 MB, over the limit of 1.0 MB"
+            ),
+            other => panic!("Expected a ConfigError, got {:?}", other),
         }
 
-        client_builder.build()
+        let log = Log::builder()
+            .path("test-path")
+            .response(&"a".repeat(1_500_000))
+            .build()
+            .unwrap();
+
+        assert!(matches!(client.log(log).await, Err(Error::ConfigError(_))));
+        assert_eq!(mock.hits(), 0);
     }
 
-    fn check_standard_result(result: Result<Response, Error>) {
-        match result {
+    #[tokio::test]
+    async fn test_run_request_id() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(200)
+                .header("x-request-id", "req-42")
+                .json_body(json!({
+                    "uuid": "123e4567-e89b-12d3-a456-426614174000",
+                    "response": { "text": "Answer", "usage": {} }
+                }));
+        });
+
+        let response = test_util::client(&server).prompt("test-path").send().await;
+
+        match response {
             Ok(Response::Json(response)) => {
+                assert_eq!(response.request_id.as_deref(), Some("req-42"));
                 assert_eq!(response.uuid, "123e4567-e89b-12d3-a456-426614174000");
-                assert_eq!(response.response.text, "Test response");
-                assert_eq!(response.response.usage.prompt_tokens, Some(10));
-                assert_eq!(response.response.usage.completion_tokens, Some(20));
-                assert_eq!(response.response.usage.total_tokens, Some(30));
-            }
-            Ok(other) => {
-                panic!(
-                    "Expected JSON response but received a different result: {:?}",
-                    other
-                );
-            }
-            Err(e) => {
-                panic!("Error occurred while running document: {:?}", e);
             }
+            other => panic!("Expected a JSON response, got {:?}", other),
         }
+        mock.assert();
     }
 
-    // Helper function to setup a Mock with a standard JSON response
-    async fn setup_standard_mock(server: &MockServer) -> Mock<'_> {
-        server.mock(|when, then| {
+    #[tokio::test]
+    async fn test_run_batch_fail_fast() {
+        let server = MockServer::start_async().await;
+        let run_mock = |message: &str, status: u16, delay: Duration| {
+            server.mock(|when, then| {
+                when.method(POST)
+                    .path("/projects/12345/versions/live/documents/run")
+                    .json_body_partial(
+                        json!({ "parameters": { "user_message": message } }).to_string(),
+                    );
+                then.status(status).delay(delay).json_body(json!({
+                    "uuid": "123e4567-e89b-12d3-a456-426614174000",
+                    "response": { "text": "Answer", "usage": {} }
+                }));
+            })
+        };
+        let slow = run_mock("first", 200, Duration::from_secs(10));
+        let failing = run_mock("second", 400, Duration::ZERO);
+        let remaining = run_mock("third", 200, Duration::ZERO);
+
+        #[derive(Serialize, Debug)]
+        struct Params {
+            user_message: String,
+        }
+
+        let inputs = tokio_stream::iter(["first", "second", "third"]).map(|message| Params {
+            user_message: message.to_owned(),
+        });
+
+        let client = test_util::client(&server);
+        let results = tokio::time::timeout(
+            Duration::from_secs(5),
+            client
+                .run_batch_fail_fast("test-path", inputs, 2)
+                .collect::<Vec<_>>(),
+        )
+        .await
+        .expect("The batch should stop at the first error");
+
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+        assert_eq!(slow.hits(), 1);
+        assert_eq!(failing.hits(), 1);
+        assert_eq!(remaining.hits(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_chat_without_stream() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
             when.method(POST)
-                .path("/projects/12345/versions/live/documents/run")
-                .header("authorization", "Bearer test_api_key")
-                .header("content-type", "application/json");
+                .path("/conversations/conversation-uuid/chat")
+                .json_body_partial(json!({ "stream": false }).to_string());
             then.status(200).json_body(json!({
-                "uuid": "123e4567-e89b-12d3-a456-426614174000",
+                "uuid": "conversation-uuid",
                 "response": {
-                    "text": "Test response",
+                    "text": "Fine, thanks!",
                     "usage": {
                         "prompt_tokens": 10,
                         "completion_tokens": 20,
@@ -674,314 +5216,539 @@ mod tests {
                     }
                 }
             }));
-        })
-    }
+        });
 
-    /// Helper function to set up a streaming mock event
-    async fn setup_mock_with_stream_event<'a>(
-        server: &'a MockServer,
-        event_name: &'a str,
-        event_data: &'a str,
-    ) -> Mock<'a> {
-        server.mock(|when, then| {
-            when.method(POST)
-                .path("/projects/12345/versions/live/documents/run")
-                .header("authorization", "Bearer test_api_key")
-                .header("content-type", "application/json");
-            then.status(200)
-                .body(format!("event: {}\ndata: {}\n\n", event_name, event_data));
-        })
+        let chat = Chat::user("conversation-uuid".to_owned(), "How are you?");
+        let response = test_util::client(&server).chat(chat).await;
+
+        assert!(matches!(
+            response,
+            Ok(Response::Json(response)) if response.response.text == "Fine, thanks!"
+        ));
+        mock.assert();
     }
 
     #[tokio::test]
-    async fn test_client_creation_with_builder() {
-        let client = setup_client(
-            "test_api_key",
-            Some(12345),
-            Some("test-version"),
-            Some("https://test.url/api"),
-        );
+    async fn test_chat_message_includes_tool_calls() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/conversations/conversation-uuid/chat");
+            then.status(200)
+                .header("content-type", "text/event-stream")
+                .body(test_util::sse_body(&[
+                    (
+                        "provider-event",
+                        r#"{"type":"text-delta","textDelta":"Let me check."}"#,
+                    ),
+                    (
+                        "provider-event",
+                        r#"{"type":"tool-call","toolCallId":"call-1","toolName":"get_weather","args":{"city":"Zurich"}}"#,
+                    ),
+                ]));
+        });
 
-        assert_eq!(client.api_key, "test_api_key");
-        assert_eq!(client.project_id, Some(12345));
-        assert_eq!(client.version_id, Some("test-version".to_string()));
-        assert_eq!(client.base_url, "https://test.url/api");
+        let chat = Chat::user("conversation-uuid".to_owned(), "What's the weather?");
+        let message = test_util::client(&server)
+            .chat_message(chat)
+            .await
+            .expect("Failed to chat");
+
+        assert_eq!(message.role, Role::Assistant);
+        assert_eq!(message.content, "Let me check.");
+        let tool_calls = message.tool_calls.expect("Missing tool calls");
+        assert_eq!(tool_calls.len(), 1);
+        assert_eq!(tool_calls[0].id, "call-1");
+        assert_eq!(tool_calls[0].name, "get_weather");
+        assert_eq!(tool_calls[0].arguments, json!({ "city": "Zurich" }));
+        mock.assert();
     }
 
     #[tokio::test]
-    async fn test_client_creation_with_default_base_url() {
-        let client = setup_client("test_api_key", Some(12345), Some("test-version"), None);
+    async fn test_list_documents() {
+        let server = MockServer::start_async().await;
+        let document = |id: i64, path: &str, model: &str| {
+            json!({
+                "id": id,
+                "documentUuid": format!("doc-uuid-{}", id),
+                "path": path,
+                "content": "Hello",
+                "resolvedContent": "Hello",
+                "contentHash": format!("hash-{}", id),
+                "commitId": 1,
+                "deletedAt": null,
+                "createdAt": "2024-11-01T00:00:00Z",
+                "updatedAt": "2024-11-01T00:00:00Z",
+                "mergedAt": "2024-11-01T00:00:00Z",
+                "projectId": 12345,
+                "config": { "provider": "OpenAI", "model": model }
+            })
+        };
 
-        assert_eq!(client.api_key, "test_api_key");
-        assert_eq!(client.project_id, Some(12345));
-        assert_eq!(client.version_id, Some("test-version".to_string()));
-        assert_eq!(client.base_url, "https://gateway.latitude.so/api/v2");
+        let mock = server.mock(|when, then| {
+            when.method("GET")
+                .path("/projects/12345/versions/live/documents");
+            then.status(200).json_body(json!([
+                document(1, "Workers/EmotionAnalyzer", "gpt-4o-mini"),
+                document(2, "Agents/Assistant", "gpt-4o")
+            ]));
+        });
+
+        let documents = test_util::client(&server)
+            .list_documents(None)
+            .await
+            .expect("Failed to list documents");
+
+        let paths: Vec<&str> = documents.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(paths, vec!["Workers/EmotionAnalyzer", "Agents/Assistant"]);
+        assert_eq!(documents[0].config.model, "gpt-4o-mini");
+        assert_eq!(documents[1].config.provider, "OpenAI");
+        assert_eq!(documents[1].config.model, "gpt-4o");
+        mock.assert();
     }
 
     #[tokio::test]
-    async fn test_client_creation_new_with_default_base_url() {
-        let client = Client::new(
-            "test_api_key".into(),
-            Some(12345),
-            Some("test-version".into()),
-            None,
+    async fn test_list_and_run_documents_under() {
+        let server = MockServer::start_async().await;
+        let document = |id: i64, path: &str| {
+            json!({
+                "id": id,
+                "documentUuid": format!("doc-uuid-{}", id),
+                "path": path,
+                "content": "Hello",
+                "resolvedContent": "Hello",
+                "contentHash": format!("hash-{}", id),
+                "commitId": 1,
+                "deletedAt": null,
+                "createdAt": "2024-11-01T00:00:00Z",
+                "updatedAt": "2024-11-01T00:00:00Z",
+                "mergedAt": "2024-11-01T00:00:00Z",
+                "projectId": 12345,
+                "config": { "provider": "OpenAI", "model": "gpt-4o-mini" }
+            })
+        };
+
+        let list_mock = server.mock(|when, then| {
+            when.method("GET")
+                .path("/projects/12345/versions/live/documents");
+            then.status(200).json_body(json!([
+                document(1, "Workers/EmotionAnalyzer"),
+                document(2, "Agents/Assistant"),
+                document(3, "Workers/Nested/Summarizer"),
+                document(4, "WorkersArchive/Old")
+            ]));
+        });
+        let run_mocks = ["Workers/EmotionAnalyzer", "Workers/Nested/Summarizer"].map(|path| {
+            server.mock(|when, then| {
+                when.method(POST)
+                    .path("/projects/12345/versions/live/documents/run")
+                    .json_body(json!({
+                        "path": path,
+                        "parameters": { "user_message": "Hello" },
+                        "stream": false
+                    }));
+                then.status(200).json_body(json!({
+                    "uuid": "123e4567-e89b-12d3-a456-426614174000",
+                    "response": { "text": format!("Answer from {}", path), "usage": {} }
+                }));
+            })
+        });
+
+        let client = test_util::client(&server);
+
+        let documents = client
+            .list_documents_under("Workers/*", None)
+            .await
+            .expect("Failed to list documents");
+        let paths: Vec<&str> = documents.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["Workers/EmotionAnalyzer", "Workers/Nested/Summarizer"]
         );
 
-        assert_eq!(client.api_key, "test_api_key");
-        assert_eq!(client.project_id, Some(12345));
-        assert_eq!(client.version_id, Some("test-version".to_string()));
-        assert_eq!(client.base_url, "https://gateway.latitude.so/api/v2");
+        let results = client
+            .run_all_under("Workers", Some(json!({ "user_message": "Hello" })), None, 2)
+            .await
+            .expect("Failed to list documents");
+        let texts: Vec<(String, String)> = results
+            .into_iter()
+            .map(|(path, result)| (path, result.expect("Failed to run").response.text))
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                (
+                    "Workers/EmotionAnalyzer".to_owned(),
+                    "Answer from Workers/EmotionAnalyzer".to_owned()
+                ),
+                (
+                    "Workers/Nested/Summarizer".to_owned(),
+                    "Answer from Workers/Nested/Summarizer".to_owned()
+                ),
+            ]
+        );
+
+        list_mock.assert_hits(2);
+        for mock in run_mocks {
+            mock.assert();
+        }
     }
 
     #[tokio::test]
-    async fn test_run_document_json_response() {
+    async fn test_print_to_writer() {
         let server = MockServer::start_async().await;
-        let mock = setup_standard_mock(&server).await;
-
-        let client = setup_client(
-            "test_api_key",
-            Some(12345),
-            Some("live"),
-            Some(&server.base_url()),
+        let mock = test_util::mock_run_stream(
+            &server,
+            "test-path",
+            &[
+                (
+                    "latitude-event",
+                    r#"{"type":"chain-step","isLastStep":true,"config":{"provider":"Latitude","model":"gpt-4o-mini"},"messages":[],"uuid":"58e86f35-293c-4f12-a412-9915cb385850"}"#,
+                ),
+                (
+                    "provider-event",
+                    r#"{"type":"text-delta","textDelta":"Once upon"}"#,
+                ),
+                (
+                    "provider-event",
+                    r#"{"type":"text-delta","textDelta":" a time"}"#,
+                ),
+            ],
         );
 
         let document = RunDocument::<()>::builder()
-            .path("test-path".into())
-            .build()
-            .expect("Failed to build RunDocument");
+            .path("test-path".to_string())
+            .build();
 
-        let result = client.run(document).await;
-        check_standard_result(result);
+        let mut output = vec![];
+        let response = test_util::client(&server)
+            .print_to(document, &mut output)
+            .await
+            .expect("Failed to run document");
+
+        assert_eq!(String::from_utf8(output).unwrap(), "Once upon a time\n");
+        assert_eq!(response.response.text, "Once upon a time");
         mock.assert();
     }
 
     #[tokio::test]
-    async fn test_run_document_with_options() {
+    async fn test_unknown_event_stream() {
+        // Tests `unknown-event` streaming response
         let server = MockServer::start_async().await;
-        let mock = setup_standard_mock(&server).await;
-
-        let client = setup_client(
-            "test_api_key",
-            Some(12345),
-            Some("live"),
-            Some(&server.base_url()),
-        );
+        let mock = setup_mock_with_stream_event(
+            &server,
+            "unknown-event",
+            r#"{"type":"text-delta","textDelta": "running"}"#,
+        )
+        .await;
 
-        let options = Options::builder()
+        let client = Client::builder("test_api_key".to_string())
             .project_id(12345)
-            .version_id("live".into())
+            .version_id("live".to_string())
+            .base_url(server.base_url())
+            .build()
+            .unwrap();
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_string())
+            .stream()
             .build();
 
-        #[derive(Serialize, Debug, Default)]
-        struct Params {
-            user_message: String,
+        let result = client
+            .run(document)
+            .await
+            .expect("Expected a stream response");
+
+        if let Response::Stream(mut stream) = result {
+            if let Some(Ok(event)) = stream.next().await {
+                match event {
+                    Event::UnknownEvent => {}
+                    _ => panic!("Expected UnknownEvent"),
+                }
+            } else {
+                panic!("Expected an event in the stream");
+            }
+        } else {
+            panic!("Expected stream response");
         }
 
-        let parameters = Params {
-            user_message: "Hello, world!".to_owned(),
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_metrics_record_first_event() {
+        let delta = |text: &str| {
+            format!(
+                "event: provider-event\ndata: {}\n\n",
+                json!({ "type": "text-delta", "textDelta": text })
+            )
+            .into_bytes()
         };
+        let base_url = serve_chunks(vec![delta("Hello"), delta(" world")], true).await;
 
-        let document = RunDocument::builder()
-            .path("test-path".into())
-            .options(options)
-            .parameters(parameters)
+        let timings = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = timings.clone();
+
+        let client = Client::builder(test_util::API_KEY.into())
+            .project_id(test_util::PROJECT_ID)
+            .base_url(base_url)
+            .on_metrics(Arc::new(move |timing: &Timing| {
+                recorded.lock().unwrap().push(*timing);
+            }))
             .build()
-            .expect("Failed to build RunDocument");
+            .unwrap();
 
-        let result = client.run(document).await;
-        check_standard_result(result);
-        mock.assert();
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_string())
+            .stream()
+            .build();
+
+        if let Ok(Response::Stream(mut stream)) = client.run(document).await {
+            while stream.next().await.is_some() {}
+        } else {
+            panic!("Expected stream response");
+        }
+
+        let timings = timings.lock().unwrap();
+        assert_eq!(timings.len(), 1);
+        let first_event = timings[0].first_event.expect("Missing first event timing");
+        assert!(timings[0].request_sent <= first_event);
+        assert!(first_event < timings[0].completed);
     }
 
     #[tokio::test]
-    async fn test_run_document_no_project_id() {
+    async fn test_small_stream_buffer() {
         let server = MockServer::start_async().await;
-        let client = setup_client("test_api_key", None, Some("live"), Some(&server.base_url()));
+        let events: Vec<String> = (0..20)
+            .map(|i| json!({ "type": "text-delta", "textDelta": i.to_string() }).to_string())
+            .collect();
+        let events: Vec<(&str, &str)> = events
+            .iter()
+            .map(|data| ("provider-event", data.as_str()))
+            .collect();
+        test_util::mock_run_stream(&server, "test-path", &events);
+
+        let client = Client::builder(test_util::API_KEY.into())
+            .project_id(test_util::PROJECT_ID)
+            .base_url(server.base_url())
+            .stream_buffer(2)
+            .build()
+            .unwrap();
 
-        let document = RunDocument::<()>::builder()
-            .path("test-path".into())
+        let mut stream = client
+            .prompt("test-path")
+            .send_stream()
+            .await
+            .expect("Expected a stream");
+
+        let mut deltas = vec![];
+        while let Some(event) = stream.next().await {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            deltas.push(event.unwrap().text_delta().unwrap().to_owned());
+        }
+
+        let expected: Vec<String> = (0..20).map(|i| i.to_string()).collect();
+        assert_eq!(deltas, expected);
+
+        let result = Client::builder(test_util::API_KEY.into())
+            .stream_buffer(0)
+            .build();
+        assert!(matches!(result, Err(Error::ConfigError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_stream_idle_timeout() {
+        let event = format!(
+            "event: provider-event\ndata: {}\n\n",
+            json!({ "type": "text-delta", "textDelta": "Hello" })
+        );
+        let base_url = serve_chunks(vec![event.into_bytes()], false).await;
+
+        let client = Client::builder(test_util::API_KEY.into())
+            .project_id(test_util::PROJECT_ID)
+            .base_url(base_url)
+            .stream_idle_timeout(Duration::from_millis(100))
             .build()
-            .expect("Failed to build RunDocument");
+            .unwrap();
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_string())
+            .stream()
+            .build();
 
-        let result = client.run(document).await;
+        let Ok(Response::Stream(mut stream)) = client.run(document).await else {
+            panic!("Expected stream response");
+        };
 
-        // Expect an error due to missing project ID
+        let first = stream.next().await.unwrap().unwrap();
+        assert_eq!(first.text_delta(), Some("Hello"));
+
+        let next = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("The idle timeout didn't fire");
         assert!(
-            matches!(result, Err(Error::ConfigError(msg)) if msg.contains("Project ID is required"))
+            matches!(next, Some(Err(Error::Other(message))) if message == "stream idle timeout")
         );
+        assert!(stream.next().await.is_none());
     }
 
     #[tokio::test]
-    async fn test_run_document_no_version_id() {
-        let server = MockServer::start_async().await;
-        let mock = setup_standard_mock(&server).await;
-
-        let client = setup_client("test_api_key", Some(12345), None, Some(&server.base_url()));
+    async fn test_chat_stream_idle_timeout() {
+        let event = test_util::sse_body(&[(
+            "provider-event",
+            r#"{"type":"text-delta","textDelta":"Still here"}"#,
+        )]);
+        let base_url = serve_chunks(vec![event.into_bytes()], false).await;
 
-        let document = RunDocument::<()>::builder()
-            .path("test-path".into())
+        let client = Client::builder(test_util::API_KEY.into())
+            .base_url(base_url)
+            .stream_idle_timeout(Duration::from_millis(100))
             .build()
-            .expect("Failed to build RunDocument");
+            .unwrap();
 
-        let result = client.run(document).await;
-        check_standard_result(result);
-        mock.assert();
+        let mut chat = Chat::user("conversation-uuid".to_owned(), "Are you there?");
+        chat.stream = true;
+
+        let Ok(Response::Stream(mut stream)) = client.chat(chat).await else {
+            panic!("Expected stream response");
+        };
+
+        let first = stream.next().await.unwrap().unwrap();
+        assert_eq!(first.text_delta(), Some("Still here"));
+
+        let next = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("The idle timeout didn't fire");
+        assert!(
+            matches!(next, Some(Err(Error::Other(message))) if message == "stream idle timeout")
+        );
+        assert!(stream.next().await.is_none());
     }
 
     #[tokio::test]
-    async fn test_latitude_event_stream() {
-        // Tests `latitude-event` streaming response
+    async fn test_custom_event_names() {
         let server = MockServer::start_async().await;
-        let mock = setup_mock_with_stream_event(
-            &server,
-            "latitude-event",
-            r#"{"type":"chain-step","isLastStep":true,"config":{"provider":"Latitude","model":"gpt-4o-mini"},"messages":[{"role":"system","content":"Generate a joke"}],"uuid":"58e86f35-293c-4f12-a412-9915cb385850"}"#
-        ).await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(200)
+                .header("content-type", "text/event-stream")
+                .body(test_util::sse_body(&[
+                    ("llm", r#"{"type":"text-delta","textDelta":"Hi"}"#),
+                    ("provider-event", r#"{"type":"text-delta","textDelta":"!"}"#),
+                ]));
+        });
 
-        let client = Client::builder("test_api_key".to_string())
-            .project_id(12345)
-            .version_id("live".to_string())
+        let client = Client::builder(test_util::API_KEY.into())
+            .project_id(test_util::PROJECT_ID)
             .base_url(server.base_url())
-            .build();
+            .event_names("lat", "llm")
+            .build()
+            .unwrap();
 
         let document = RunDocument::<()>::builder()
             .path("test-path".to_string())
             .stream()
-            .build()
-            .expect("Failed to build RunDocument");
-
-        let result = client
-            .run(document)
-            .await
-            .expect("Expected a stream response");
+            .build();
 
-        if let Response::Stream(mut stream) = result {
-            if let Some(event) = stream.recv().await {
-                match event {
-                    Event::LatitudeEvent(data) => {
-                        assert_eq!(
-                            data.event_type,
-                            LatitudeEventType::ChainStep(ChainStep {
-                                is_last_step: true,
-                                config: Config {
-                                    provider: "Latitude".to_string(),
-                                    model: "gpt-4o-mini".to_string()
-                                },
-                                messages: vec![Message {
-                                    role: Role::System,
-                                    tool_calls: None,
-                                    content: "Generate a joke".to_string()
-                                }],
-                                uuid: Uuid::from_str("58e86f35-293c-4f12-a412-9915cb385850")
-                                    .expect("Failed to parse UUID"),
-                            })
-                        );
-                    }
-                    _ => panic!("Expected LatitudeEvent"),
-                }
-            } else {
-                panic!("Expected an event in the stream");
-            }
-        } else {
+        let Ok(Response::Stream(mut stream)) = client.run(document).await else {
             panic!("Expected stream response");
-        }
+        };
 
+        let first = stream.next().await.unwrap().unwrap();
+        assert_eq!(first.text_delta(), Some("Hi"));
+        assert!(matches!(stream.next().await, Some(Ok(Event::UnknownEvent))));
+        assert!(stream.next().await.is_none());
         mock.assert();
     }
 
     #[tokio::test]
-    async fn test_provider_event_stream() {
-        // Tests `provider-event` streaming response
+    async fn test_stream_progress_callback() {
         let server = MockServer::start_async().await;
+        let step_finish = |prompt: usize, completion: usize| {
+            format!(
+                "event: provider-event\ndata: {}\n\n",
+                json!({
+                    "type": "step-finish",
+                    "finishReason": "stop",
+                    "usage": {
+                        "promptTokens": prompt,
+                        "completionTokens": completion,
+                        "totalTokens": prompt + completion
+                    },
+                    "response": {
+                        "id": "resp-1",
+                        "timestamp": "2024-11-01T00:00:00Z",
+                        "modelId": "gpt-4o-mini"
+                    },
+                    "isContinued": false
+                })
+            )
+        };
+        let body = format!("{}{}", step_finish(10, 5), step_finish(10, 25));
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(200).body(body);
+        });
 
-        let mock = setup_mock_with_stream_event(
-            &server,
-            "provider-event",
-            r#"{"type":"text-delta","textDelta": "running"}"#,
-        )
-        .await;
+        let totals = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = totals.clone();
 
         let client = Client::builder("test_api_key".to_string())
             .project_id(12345)
-            .version_id("live".to_string())
             .base_url(server.base_url())
-            .build();
+            .on_stream_progress(Arc::new(move |usage: &Usage| {
+                recorded.lock().unwrap().push(usage.total_tokens);
+            }))
+            .build()
+            .unwrap();
 
         let document = RunDocument::<()>::builder()
             .path("test-path".to_string())
             .stream()
-            .build()
-            .expect("Failed to build RunDocument");
-
-        let result = client
-            .run(document)
-            .await
-            .expect("Expected a stream response");
+            .build();
 
-        if let Response::Stream(mut stream) = result {
-            if let Some(event) = stream.recv().await {
-                match event {
-                    Event::ProviderEvent(data) => {
-                        assert_eq!(
-                            data.event_type,
-                            ProviderEventType::TextDelta(TextDelta {
-                                text_delta: "running".to_string(),
-                            })
-                        );
-                    }
-                    _ => panic!("Expected ProviderEvent"),
-                }
-            } else {
-                panic!("Expected an event in the stream");
-            }
+        if let Ok(Response::Stream(mut stream)) = client.run(document).await {
+            while stream.next().await.is_some() {}
         } else {
             panic!("Expected stream response");
         }
 
+        assert_eq!(*totals.lock().unwrap(), vec![Some(15), Some(35)]);
         mock.assert();
     }
 
     #[tokio::test]
-    async fn test_unknown_event_stream() {
-        // Tests `unknown-event` streaming response
+    async fn test_stream_with_bom_prefix() {
         let server = MockServer::start_async().await;
-        let mock = setup_mock_with_stream_event(
-            &server,
-            "unknown-event",
-            r#"{"type":"text-delta","textDelta": "running"}"#,
-        )
-        .await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(200).body(concat!(
+                "\u{feff}\n  event: provider-event\n",
+                r#"data: {"type":"text-delta","textDelta": "running"}"#,
+                "\n\n"
+            ));
+        });
 
-        let client = Client::builder("test_api_key".to_string())
-            .project_id(12345)
-            .version_id("live".to_string())
-            .base_url(server.base_url())
-            .build();
+        let client = setup_client("test_api_key", Some(12345), None, Some(&server.base_url()));
 
         let document = RunDocument::<()>::builder()
             .path("test-path".to_string())
             .stream()
-            .build()
-            .expect("Failed to build RunDocument");
-
-        let result = client
-            .run(document)
-            .await
-            .expect("Expected a stream response");
+            .build();
 
-        if let Response::Stream(mut stream) = result {
-            if let Some(event) = stream.recv().await {
-                match event {
-                    Event::UnknownEvent => {
-                        assert!(true)
-                    }
-                    _ => panic!("Expected UnknownEvent"),
-                }
-            } else {
-                panic!("Expected an event in the stream");
-            }
-        } else {
-            panic!("Expected stream response");
+        match client.run(document).await {
+            Ok(Response::Stream(mut stream)) => match stream.next().await {
+                Some(Ok(Event::ProviderEvent(data))) => assert_eq!(
+                    data.event_type,
+                    ProviderEventType::TextDelta(TextDelta {
+                        text_delta: "running".to_string(),
+                    })
+                ),
+                other => panic!("Expected ProviderEvent, got {:?}", other),
+            },
+            other => panic!("Expected stream response, got {:?}", other),
         }
 
         mock.assert();
@@ -992,45 +5759,36 @@ mod tests {
         // This test checks if the function handles a streaming error properly
         let server = MockServer::start_async().await;
 
-        // Mock server response with an invalid streaming format to trigger an error
+        // Mock server response with a malformed event payload to trigger an error
         let mock = server.mock(|when, then| {
             when.method(POST)
                 .path("/projects/12345/versions/live/documents/run")
                 .header("authorization", "Bearer test_api_key")
                 .header("content-type", "application/json");
-            then.status(200).body("data: invalid-format\n\n");
+            then.status(200)
+                .body("event: latitude-event\ndata: invalid-format\n\n");
         });
 
         let client = Client::builder("test_api_key".to_string())
             .project_id(12345)
             .version_id("live".to_string())
             .base_url(server.base_url())
-            .build();
+            .build()
+            .unwrap();
 
         let document = RunDocument::<()>::builder()
             .path("test-path".to_string())
             .stream()
-            .build()
-            .expect("Failed to build RunDocument");
+            .build();
 
         let result = client.run(document).await;
 
         if let Ok(Response::Stream(mut stream)) = result {
-            if let Some(event) = stream.recv().await {
-                match event {
-                    Event::UnknownEvent => {
-                        println!("Received UnknownEvent as expected.");
-                    }
-                    other_event => {
-                        panic!(
-                            "Expected UnknownEvent but got a different event type: {:?}",
-                            other_event
-                        );
-                    }
-                }
-            } else {
-                panic!("Expected an event in the stream, but none was received.");
+            match stream.next().await {
+                Some(Err(Error::SerializationError(_))) => {}
+                other => panic!("Expected a serialization error, got {:?}", other),
             }
+            assert!(stream.next().await.is_none());
         } else {
             panic!("Expected stream response, but got an error: {:?}", result);
         }
@@ -1038,13 +5796,108 @@ mod tests {
         mock.assert();
     }
 
+    #[tokio::test]
+    async fn test_stream_with_json_error_body() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "name": "DocumentRunError",
+                    "errorCode": "DocumentConfigError",
+                    "message": "Invalid document configuration",
+                    "details": {}
+                }));
+        });
+
+        let client = setup_client("test_api_key", Some(12345), None, Some(&server.base_url()));
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_string())
+            .stream()
+            .build();
+
+        let result = client.run(document).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::ResponseFormatError(msg)) if msg.contains("Invalid document configuration")
+        ));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_error_body_surfaced() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(422).json_body(json!({
+                "name": "DocumentRunError",
+                "message": "Missing parameter: user_message",
+                "errorCode": "DocumentConfigError",
+                "details": { "parameter": "user_message" }
+            }));
+        });
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_owned())
+            .build();
+        let result = test_util::client(&server).run(document).await;
+
+        match result {
+            Err(Error::ApiResponse(error)) => {
+                assert_eq!(error.name, "DocumentRunError");
+                assert_eq!(error.message, "Missing parameter: user_message");
+                assert!(matches!(
+                    error.error_code,
+                    ApiResponseCode::RunError(RunErrorCodes::DocumentConfigError)
+                ));
+                assert_eq!(error.details, json!({ "parameter": "user_message" }));
+            }
+            other => panic!("Expected an API error response, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_body_with_unknown_code_surfaced() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/live/documents/run");
+            then.status(402).json_body(json!({
+                "name": "PaymentRequiredError",
+                "message": "Your plan ran out of credits",
+                "errorCode": "PaymentRequiredError"
+            }));
+        });
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".to_owned())
+            .build();
+        let result = test_util::client(&server).run(document).await;
+
+        match result {
+            Err(Error::ApiResponse(error)) => {
+                assert_eq!(error.message, "Your plan ran out of credits");
+                assert!(matches!(
+                    &error.error_code,
+                    ApiResponseCode::Other(code) if code == "PaymentRequiredError"
+                ));
+            }
+            other => panic!("Expected an API error response, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_check_status() {
         // Test TOO_MANY_REQUESTS status
         let result = Client::check_status(StatusCode::TOO_MANY_REQUESTS);
         assert!(matches!(
             result,
-            Err(Error::LatitudeError(LatitudeErrorCodes::RateLimitError))
+            Err(Error::RateLimited { retry_after: None })
         ));
 
         // Test UNAUTHORIZED status
@@ -1091,6 +5944,25 @@ mod tests {
             ))
         ));
 
+        // Test server errors
+        for status in [
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::SERVICE_UNAVAILABLE,
+        ] {
+            let result = Client::check_status(status);
+            assert!(matches!(
+                result,
+                Err(Error::ApiError(ApiErrorCodes::InternalServerError))
+            ));
+        }
+
+        // Test an unmapped client error
+        let result = Client::check_status(StatusCode::PAYMENT_REQUIRED);
+        assert!(matches!(
+            result,
+            Err(Error::ApiError(ApiErrorCodes::HTTPException))
+        ));
+
         // Test OK status (expecting no error)
         let result = Client::check_status(StatusCode::OK);
         assert!(matches!(result, Ok(())));
@@ -1136,8 +6008,7 @@ mod tests {
 
         let result = client.get("test-path", None).await;
 
-        if result.is_ok() {
-            let document = result.unwrap();
+        if let Ok(document) = result {
             assert_eq!(document.path, "test-path");
             assert_eq!(document.content, "Test content");
         } else {
@@ -1147,6 +6018,63 @@ mod tests {
         mock.assert();
     }
 
+    #[tokio::test]
+    async fn test_get_document_parameters() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method("GET")
+                .path("/projects/12345/versions/live/documents/test-path");
+            then.status(200).json_body(json!({
+                "id": 1,
+                "documentUuid": "doc-uuid",
+                "path": "test-path",
+                "content": "Hello {{name}}, {{greeting}}",
+                "resolvedContent": "Hello {{name}}, {{greeting}}",
+                "contentHash": "hash123",
+                "commitId": 100,
+                "deletedAt": null,
+                "createdAt": "2024-11-01T00:00:00Z",
+                "updatedAt": "2024-11-02T00:00:00Z",
+                "mergedAt": null,
+                "projectId": 12345,
+                "config": { "provider": "OpenAI", "model": "gpt-4o-mini" },
+                "parameters": [
+                    { "name": "name", "type": "text", "required": true },
+                    { "name": "greeting", "type": "text", "default": "Welcome!" },
+                    { "name": "avatar" }
+                ]
+            }));
+        });
+
+        let document = test_util::client(&server)
+            .get("test-path", None)
+            .await
+            .expect("Failed to get document");
+
+        assert_eq!(
+            document.parameters,
+            vec![
+                DocumentParameter {
+                    name: "name".to_owned(),
+                    r#type: Some("text".to_owned()),
+                    required: true,
+                    default: None,
+                },
+                DocumentParameter {
+                    name: "greeting".to_owned(),
+                    r#type: Some("text".to_owned()),
+                    required: false,
+                    default: Some(json!("Welcome!")),
+                },
+                DocumentParameter {
+                    name: "avatar".to_owned(),
+                    ..Default::default()
+                },
+            ]
+        );
+        mock.assert();
+    }
+
     #[tokio::test]
     async fn test_get_document_missing_project_id() {
         let client = setup_client(
@@ -1207,16 +6135,14 @@ mod tests {
 
         let result = client.log(log).await;
 
-        /*         if result.is_ok() {
-            let document = result.unwrap();
+        /*         if let Ok(document) = result {
             assert_eq!(document.path, "test-path");
             assert_eq!(document.content, "Test content");
         } else {
             eprintln!("Test failed with error: {:?}", result);
         } */
 
-        if result.is_ok() {
-            let log_response = result.unwrap();
+        if let Ok(log_response) = result {
             assert_eq!(log_response.id, 1);
             assert_eq!(log_response.source, "test");
         } else {
@@ -1225,14 +6151,60 @@ mod tests {
         mock.assert();
     }
 
+    #[tokio::test]
+    async fn test_wait_for_evaluation_pending_then_ready() {
+        static CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+        fn first_call(_: &httpmock::prelude::HttpMockRequest) -> bool {
+            CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0
+        }
+
+        let server = MockServer::start_async().await;
+        let path = "/conversations/test-convo/evaluations/eval-123/result";
+        let pending = server.mock(|when, then| {
+            when.method("GET").path(path).matches(first_call);
+            then.status(200).json_body(json!({
+                "uuid": "result-1",
+                "evaluationUuid": "eval-123",
+                "status": "pending",
+                "result": null,
+                "reason": null
+            }));
+        });
+        let ready = server.mock(|when, then| {
+            when.method("GET").path(path);
+            then.status(200).json_body(json!({
+                "uuid": "result-1",
+                "evaluationUuid": "eval-123",
+                "status": "completed",
+                "result": 5,
+                "reason": "Very funny"
+            }));
+        });
+
+        let client = setup_client("test_api_key", None, None, Some(&server.base_url()));
+
+        let result = client
+            .wait_for_evaluation("test-convo", "eval-123", Duration::from_secs(5))
+            .await
+            .expect("Expected evaluation result");
+
+        assert!(result.is_ready());
+        assert_eq!(result.result, Some(json!(5)));
+        assert_eq!(result.reason.as_deref(), Some("Very funny"));
+        pending.assert();
+        ready.assert();
+    }
+
     #[tokio::test]
     async fn test_eval_success() {
         let server = MockServer::start_async().await;
         let mock = server.mock(|when, then| {
             when.method("POST")
-                .path("/conversations/test-convo/chat")
+                .path("/conversations/test-convo/evaluate")
                 .header("authorization", "Bearer test_api_key")
-                .header("content-type", "application/json");
+                .header("content-type", "application/json")
+                .json_body(json!({ "evaluationUuids": ["eval-123"] }));
             then.status(200).json_body(json!({
                 "evaluations": ["positive", "relevant"]
             }));
@@ -0,0 +1,706 @@
+use std::time::Duration;
+
+use serde_json::Value;
+
+use super::document::{ResponseDetail, RunResponse, UsageDetail};
+use super::event::{
+    ChainStep, Event, LatitudeEventType, Message, ObjectDelta, ProviderEventType, Response,
+    TokenLogprob, ToolCall, ToolCallEvent, Usage,
+};
+use super::log::LogRecord;
+use super::message::Role;
+
+/// StreamAccumulator folds the events of a streamed response into its aggregated state.
+///
+/// Feed every received `Event` into `push`; the accumulator assembles the streamed text
+/// and tool calls, and records each step of a chained prompt in the order it was executed.
+///
+/// Steps completing with the `"object"` stream type stream the JSON of a structured
+/// object instead of prose; their deltas are assembled into `object` rather than `text`.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "client")] {
+/// use latitude_sdk::decode_events;
+/// use latitude_sdk::models::accumulator::StreamAccumulator;
+///
+/// let events = decode_events(b"event: provider-event\ndata: {\"type\":\"text-delta\",\"textDelta\":\"Hi\"}\n\n")
+///     .expect("Failed to decode events");
+///
+/// let mut accumulator = StreamAccumulator::new();
+/// for event in &events {
+///     accumulator.push(event);
+/// }
+///
+/// assert_eq!(accumulator.text(), "Hi");
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct StreamAccumulator {
+    text: String,
+    steps: Vec<ChainStep>,
+    pending_tool_calls: Vec<PendingToolCall>,
+    completed_tool_calls: Vec<ToolCallEvent>,
+    uuid: Option<String>,
+    final_response: Option<Response>,
+    step_usage: Vec<(String, Usage)>,
+    finish_usage: Option<Usage>,
+    logprobs: Option<Vec<TokenLogprob>>,
+    stream_type: Option<String>,
+    step_text_start: usize,
+    object: Option<Value>,
+    partial_object: Option<Value>,
+}
+
+/// Adds two token counts, which stay unknown only if neither is known.
+fn sum_tokens(a: Option<u64>, b: Option<u64>) -> Option<u64> {
+    match (a, b) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or_default() + b.unwrap_or_default()),
+    }
+}
+
+/// The stream type of responses streaming a structured object.
+const OBJECT_STREAM_TYPE: &str = "object";
+
+/// A tool call whose arguments are still being streamed.
+#[derive(Debug, Clone, PartialEq)]
+struct PendingToolCall {
+    tool_call_id: String,
+    tool_name: String,
+    args: String,
+}
+
+impl StreamAccumulator {
+    /// Creates a new, empty `StreamAccumulator`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Folds a single streamed event into the accumulated state.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event received from the stream.
+    pub fn push(&mut self, event: &Event) {
+        match event {
+            Event::LatitudeEvent(event) => match &event.event_type {
+                LatitudeEventType::ChainStep(step) => {
+                    self.uuid = Some(step.uuid.to_string());
+                    self.steps.push(step.clone());
+                    self.step_text_start = self.text.len();
+                }
+                LatitudeEventType::ChainComplete(complete) => {
+                    if let Some(uuid) = &complete.response.document_log_uuid {
+                        self.uuid = Some(uuid.clone());
+                    }
+                    self.complete_step(&complete.response);
+                    self.final_response = Some(complete.response.clone());
+                }
+                LatitudeEventType::ChainStepComplete(complete) => {
+                    self.complete_step(&complete.response);
+                    self.step_usage
+                        .push((complete.uuid.clone(), complete.response.usage.clone()));
+                }
+            },
+            Event::ProviderEvent(event) => match &event.event_type {
+                ProviderEventType::TextDelta(delta) => self.text.push_str(&delta.text_delta),
+                ProviderEventType::ObjectDelta(delta) => self.apply_object_delta(delta),
+                ProviderEventType::ToolCallDelta(delta) => {
+                    match self
+                        .pending_tool_calls
+                        .iter_mut()
+                        .find(|pending| pending.tool_call_id == delta.tool_call_id)
+                    {
+                        Some(pending) => pending.args.push_str(&delta.args_text_delta),
+                        None => self.pending_tool_calls.push(PendingToolCall {
+                            tool_call_id: delta.tool_call_id.clone(),
+                            tool_name: delta.tool_name.clone(),
+                            args: delta.args_text_delta.clone(),
+                        }),
+                    }
+                }
+                ProviderEventType::ToolCall(tool_call) => {
+                    self.pending_tool_calls
+                        .retain(|pending| pending.tool_call_id != tool_call.tool_call_id);
+                    self.completed_tool_calls.push(tool_call.clone());
+                }
+                ProviderEventType::StepFinish(finish) => {
+                    if let Some(logprobs) = &finish.logprobs {
+                        self.logprobs
+                            .get_or_insert_with(Vec::new)
+                            .extend(logprobs.iter().cloned());
+                    }
+                    self.flush_pending_tool_calls();
+                }
+                ProviderEventType::Finish(finish) => {
+                    self.finish_usage = Some(finish.usage.clone());
+                    self.flush_pending_tool_calls();
+                }
+                _ => {}
+            },
+            Event::UnknownEvent => {}
+        }
+    }
+
+    /// Records the stream type of a completed step, assembling its object in object mode.
+    ///
+    /// In object mode the deltas streamed since the step started are the object's JSON, so
+    /// they're moved out of the text and parsed. The response's own text is used when the
+    /// deltas weren't streamed.
+    fn complete_step(&mut self, response: &Response) {
+        self.stream_type = response.stream_type.clone();
+
+        if self.stream_type() == Some(OBJECT_STREAM_TYPE) {
+            let json = self
+                .text
+                .split_off(self.step_text_start.min(self.text.len()));
+            let json = if json.is_empty() {
+                &response.text
+            } else {
+                &json
+            };
+
+            if let Ok(object) = serde_json::from_str(json) {
+                self.object = Some(object);
+            }
+        }
+
+        self.step_text_start = self.text.len();
+    }
+
+    /// Applies the JSON patch of an object delta to the partial object.
+    ///
+    /// The object starts out empty. A patch that isn't valid or doesn't apply is skipped,
+    /// leaving the partial object unchanged.
+    fn apply_object_delta(&mut self, delta: &ObjectDelta) {
+        let Ok(patch) = serde_json::from_value::<json_patch::Patch>(delta.patch.clone()) else {
+            return;
+        };
+
+        let object = self
+            .partial_object
+            .get_or_insert_with(|| Value::Object(Default::default()));
+        let _ = json_patch::patch(object, &patch);
+    }
+
+    /// Returns the text assembled from the streamed text deltas so far.
+    ///
+    /// Once a step completes in object mode, its deltas are no longer part of the text;
+    /// see `object`.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the stream type of the last completed step, e.g. `"text"` or `"object"`.
+    ///
+    /// # Returns
+    /// The stream type, or `None` if no step has completed yet or the server didn't report it.
+    pub fn stream_type(&self) -> Option<&str> {
+        self.stream_type.as_deref()
+    }
+
+    /// Returns the object built so far from the streamed object deltas.
+    ///
+    /// Gateways streaming objects as JSON patches emit `object-delta` events instead of
+    /// text deltas; each patch is applied in order to build the evolving object.
+    ///
+    /// # Returns
+    /// The partial object, or `None` if no object delta has been received.
+    pub fn partial_object(&self) -> Option<Value> {
+        self.partial_object.clone()
+    }
+
+    /// Returns the structured object assembled from an object-mode stream.
+    ///
+    /// # Returns
+    /// The parsed object, or `None` if no step completed in object mode or its JSON was invalid.
+    pub fn object(&self) -> Option<&Value> {
+        self.object.as_ref()
+    }
+
+    /// Returns the tool calls completed so far, in the order they completed.
+    ///
+    /// Tool calls whose arguments are streamed as `tool-call-delta` fragments are
+    /// assembled by `tool_call_id` and complete either when the full `tool-call` event
+    /// arrives or when the step finishes.
+    pub fn completed_tool_calls(&self) -> &[ToolCallEvent] {
+        &self.completed_tool_calls
+    }
+
+    /// Returns the completed tool calls in the shape of a response's tool calls.
+    fn tool_calls(&self) -> Vec<ToolCall> {
+        self.completed_tool_calls
+            .iter()
+            .map(|tool_call| ToolCall {
+                id: tool_call.tool_call_id.clone(),
+                name: tool_call.tool_name.clone(),
+                arguments: tool_call.args.clone(),
+            })
+            .collect()
+    }
+
+    /// Assembles the assistant message of the accumulated stream, with its tool calls.
+    ///
+    /// The text and tool calls are taken from the `chain-complete` event when it was
+    /// received, falling back to the streamed text and completed tool calls otherwise.
+    ///
+    /// # Returns
+    /// The assistant `Message`, whose `tool_calls` is `None` if no tool was called.
+    pub fn assistant_message(&self) -> Message {
+        let (content, tool_calls) = match &self.final_response {
+            Some(response) => (response.text.clone(), response.tool_calls.clone()),
+            None => {
+                let tool_calls = self.tool_calls();
+                (
+                    self.text.clone(),
+                    (!tool_calls.is_empty()).then_some(tool_calls),
+                )
+            }
+        };
+
+        Message {
+            role: Role::Assistant,
+            tool_calls,
+            content,
+        }
+    }
+
+    /// Completes any tool calls still being assembled, parsing their arguments as JSON.
+    ///
+    /// Arguments that aren't valid JSON are kept as a raw string value.
+    fn flush_pending_tool_calls(&mut self) {
+        for pending in self.pending_tool_calls.drain(..) {
+            let args = serde_json::from_str(&pending.args).unwrap_or(Value::String(pending.args));
+
+            self.completed_tool_calls.push(ToolCallEvent {
+                tool_call_id: pending.tool_call_id,
+                tool_name: pending.tool_name,
+                args,
+            });
+        }
+    }
+
+    /// Returns the chain steps received so far, in execution order.
+    ///
+    /// Chained prompts emit a `ChainStep` for every step, with `is_last_step` set only
+    /// on the final one, so this gives full visibility into multi-step chains.
+    pub fn steps(&self) -> &[ChainStep] {
+        &self.steps
+    }
+
+    /// Returns the token usage of every completed chain step, keyed by the step's uuid.
+    ///
+    /// This attributes the cost of a multi-step chain to its individual steps; see
+    /// `total_usage` for the grand total.
+    pub fn step_usage(&self) -> &[(String, Usage)] {
+        &self.step_usage
+    }
+
+    /// Returns the total token usage of the chain.
+    ///
+    /// The usage reported by the `chain-complete` event is used when it was received,
+    /// otherwise the usage of the completed steps is summed, falling back to the usage of
+    /// the provider's `finish` event.
+    ///
+    /// # Returns
+    /// The total usage, or `None` if no usage has been reported yet.
+    pub fn total_usage(&self) -> Option<Usage> {
+        if let Some(response) = &self.final_response {
+            return Some(response.usage.clone());
+        }
+
+        if self.step_usage.is_empty() {
+            return self.finish_usage.clone();
+        }
+
+        Some(
+            self.step_usage
+                .iter()
+                .fold(Usage::default(), |total, (_, usage)| Usage {
+                    prompt_tokens: sum_tokens(total.prompt_tokens, usage.prompt_tokens),
+                    completion_tokens: sum_tokens(total.completion_tokens, usage.completion_tokens),
+                    total_tokens: sum_tokens(total.total_tokens, usage.total_tokens),
+                }),
+        )
+    }
+
+    /// Assembles the `RunResponse` equivalent to the accumulated stream.
+    ///
+    /// The text, usage and tool calls are taken from the `chain-complete` event when it was
+    /// received, falling back to the streamed text, `total_usage` and completed tool calls
+    /// otherwise.
+    ///
+    /// # Returns
+    /// The assembled `RunResponse`, or `None` if no event identifying the conversation
+    /// has been received yet.
+    pub fn run_response(&self) -> Option<RunResponse> {
+        let uuid = self.uuid.clone()?;
+
+        let (text, usage, tool_calls) = match &self.final_response {
+            Some(response) => (
+                response.text.clone(),
+                UsageDetail {
+                    prompt_tokens: response.usage.prompt_tokens,
+                    completion_tokens: response.usage.completion_tokens,
+                    total_tokens: response.usage.total_tokens,
+                },
+                response.tool_calls.clone().unwrap_or_default(),
+            ),
+            None => {
+                let usage = self.total_usage();
+                (
+                    self.text.clone(),
+                    UsageDetail {
+                        prompt_tokens: usage.as_ref().and_then(|usage| usage.prompt_tokens),
+                        completion_tokens: usage.as_ref().and_then(|usage| usage.completion_tokens),
+                        total_tokens: usage.as_ref().and_then(|usage| usage.total_tokens),
+                    },
+                    self.tool_calls(),
+                )
+            }
+        };
+
+        Some(RunResponse {
+            uuid,
+            response: ResponseDetail {
+                text,
+                usage,
+                logprobs: self.logprobs.clone(),
+                tool_calls,
+            },
+            request_id: None,
+        })
+    }
+
+    /// Assembles a document-log-shaped record of the accumulated stream.
+    ///
+    /// The resolved content is taken from the messages the first chain step was run with,
+    /// and the response text and usage from the `chain-complete` event when it was
+    /// received, falling back to the streamed text and summed step usage otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the document that was run.
+    /// * `parameters` - The parameters the document was run with.
+    /// * `duration` - How long the run took.
+    ///
+    /// # Returns
+    ///
+    /// The `LogRecord`, ready to serialize.
+    pub fn to_log_record(&self, path: &str, parameters: Value, duration: Duration) -> LogRecord {
+        let resolved_content = self
+            .steps
+            .first()
+            .map(|step| {
+                step.messages
+                    .iter()
+                    .map(|message| message.content.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            })
+            .unwrap_or_default();
+
+        let response = match &self.final_response {
+            Some(response) => response.text.clone(),
+            None => self.text.clone(),
+        };
+
+        LogRecord {
+            uuid: self.uuid.clone(),
+            path: path.to_owned(),
+            resolved_content,
+            parameters,
+            response,
+            usage: self.total_usage(),
+            duration: duration.as_millis() as u64,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use super::*;
+    use crate::decode_events;
+
+    fn accumulate(blob: &str) -> StreamAccumulator {
+        let mut accumulator = StreamAccumulator::new();
+        for event in decode_events(blob.as_bytes()).expect("Failed to decode events") {
+            accumulator.push(&event);
+        }
+        accumulator
+    }
+
+    #[test]
+    fn test_accumulator_captures_steps_in_order() {
+        let accumulator = accumulate(concat!(
+            "event: latitude-event\n",
+            r#"data: {"type":"chain-step","isLastStep":false,"config":{"provider":"Latitude","model":"gpt-4o-mini"},"messages":[{"role":"system","content":"Think step by step"}],"uuid":"58e86f35-293c-4f12-a412-9915cb385850"}"#,
+            "\n\n",
+            "event: provider-event\n",
+            r#"data: {"type":"text-delta","textDelta":"Thinking"}"#,
+            "\n\n",
+            "event: latitude-event\n",
+            r#"data: {"type":"chain-step","isLastStep":true,"config":{"provider":"Latitude","model":"gpt-4o"},"messages":[{"role":"user","content":"Now answer"}],"uuid":"58e86f35-293c-4f12-a412-9915cb385850"}"#,
+            "\n\n",
+        ));
+
+        let steps = accumulator.steps();
+        assert_eq!(steps.len(), 2);
+        assert!(!steps[0].is_last_step);
+        assert_eq!(steps[0].messages[0].content, "Think step by step");
+        assert!(steps[1].is_last_step);
+        assert_eq!(steps[1].config.model, "gpt-4o");
+        assert_eq!(accumulator.text(), "Thinking");
+    }
+
+    const STEP_FINISH: &str = r#"data: {"type":"step-finish","finishReason":"tool-calls","usage":{"promptTokens":10,"completionTokens":5,"totalTokens":15},"response":{"id":"resp-1","timestamp":"2024-11-01T00:00:00Z","modelId":"gpt-4o-mini"},"isContinued":true}"#;
+
+    #[test]
+    fn test_accumulator_assembles_tool_call_deltas() {
+        let blob = [
+            r#"data: {"type":"tool-call-delta","toolCallId":"call-1","toolName":"get_weather","argsTextDelta":"{\"city\":"}"#,
+            r#"data: {"type":"tool-call-delta","toolCallId":"call-2","toolName":"get_time","argsTextDelta":"{}"}"#,
+            r#"data: {"type":"tool-call-delta","toolCallId":"call-1","toolName":"get_weather","argsTextDelta":"\"Berlin\"}"}"#,
+            STEP_FINISH,
+        ]
+        .iter()
+        .map(|data| format!("event: provider-event\n{}\n\n", data))
+        .collect::<String>();
+
+        let accumulator = accumulate(&blob);
+
+        assert_eq!(
+            accumulator.completed_tool_calls(),
+            &[
+                ToolCallEvent {
+                    tool_call_id: "call-1".to_owned(),
+                    tool_name: "get_weather".to_owned(),
+                    args: serde_json::json!({ "city": "Berlin" }),
+                },
+                ToolCallEvent {
+                    tool_call_id: "call-2".to_owned(),
+                    tool_name: "get_time".to_owned(),
+                    args: serde_json::json!({}),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_accumulator_completes_tool_call_once() {
+        let blob = [
+            r#"data: {"type":"tool-call-delta","toolCallId":"call-1","toolName":"get_weather","argsTextDelta":"{\"city\":\"Berlin\"}"}"#,
+            r#"data: {"type":"tool-call","toolCallId":"call-1","toolName":"get_weather","args":{"city":"Berlin"}}"#,
+            STEP_FINISH,
+        ]
+        .iter()
+        .map(|data| format!("event: provider-event\n{}\n\n", data))
+        .collect::<String>();
+
+        let accumulator = accumulate(&blob);
+
+        assert_eq!(accumulator.completed_tool_calls().len(), 1);
+        assert_eq!(
+            accumulator.completed_tool_calls()[0].args,
+            serde_json::json!({ "city": "Berlin" })
+        );
+    }
+
+    fn step_complete(uuid: &str, prompt_tokens: usize, completion_tokens: usize) -> String {
+        format!(
+            "event: latitude-event\ndata: {}\n\n",
+            serde_json::json!({
+                "type": "chain-step-complete",
+                "uuid": uuid,
+                "response": {
+                    "streamType": "text",
+                    "text": "step",
+                    "usage": {
+                        "promptTokens": prompt_tokens,
+                        "completionTokens": completion_tokens,
+                        "totalTokens": prompt_tokens + completion_tokens
+                    }
+                }
+            })
+        )
+    }
+
+    #[test]
+    fn test_accumulator_step_usage() {
+        let steps = [
+            step_complete("step-1", 10, 5),
+            step_complete("step-2", 20, 8),
+        ]
+        .concat();
+        let accumulator = accumulate(&steps);
+
+        assert_eq!(
+            accumulator.step_usage(),
+            &[
+                (
+                    "step-1".to_owned(),
+                    Usage {
+                        prompt_tokens: Some(10),
+                        completion_tokens: Some(5),
+                        total_tokens: Some(15),
+                    }
+                ),
+                (
+                    "step-2".to_owned(),
+                    Usage {
+                        prompt_tokens: Some(20),
+                        completion_tokens: Some(8),
+                        total_tokens: Some(28),
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            accumulator.total_usage(),
+            Some(Usage {
+                prompt_tokens: Some(30),
+                completion_tokens: Some(13),
+                total_tokens: Some(43),
+            })
+        );
+
+        let chain_complete = concat!(
+            "event: latitude-event\n",
+            r#"data: {"type":"chain-complete","config":{"provider":"Latitude","model":"gpt-4o-mini"},"messages":[],"response":{"streamType":"text","text":"done","usage":{"promptTokens":30,"completionTokens":13,"totalTokens":45}}}"#,
+            "\n\n",
+        );
+        let accumulator = accumulate(&[steps.as_str(), chain_complete].concat());
+
+        assert_eq!(accumulator.step_usage().len(), 2);
+        assert_eq!(
+            accumulator
+                .total_usage()
+                .and_then(|usage| usage.total_tokens),
+            Some(45)
+        );
+    }
+
+    fn text_delta(text: &str) -> String {
+        format!(
+            "event: provider-event\ndata: {}\n\n",
+            serde_json::json!({ "type": "text-delta", "textDelta": text })
+        )
+    }
+
+    fn chain_complete(stream_type: &str, text: &str) -> String {
+        format!(
+            "event: latitude-event\ndata: {}\n\n",
+            serde_json::json!({
+                "type": "chain-complete",
+                "config": { "provider": "Latitude", "model": "gpt-4o-mini" },
+                "messages": [],
+                "response": {
+                    "streamType": stream_type,
+                    "text": text,
+                    "usage": { "promptTokens": 4, "completionTokens": 2, "totalTokens": 6 }
+                }
+            })
+        )
+    }
+
+    #[test]
+    fn test_accumulator_text_stream() {
+        let accumulator = accumulate(
+            &[
+                text_delta("Hello"),
+                text_delta(", world"),
+                chain_complete("text", "Hello, world"),
+            ]
+            .concat(),
+        );
+
+        assert_eq!(accumulator.stream_type(), Some("text"));
+        assert_eq!(accumulator.text(), "Hello, world");
+        assert_eq!(accumulator.object(), None);
+    }
+
+    #[test]
+    fn test_accumulator_object_stream() {
+        let accumulator = accumulate(
+            &[
+                text_delta(r#"{"city":"#),
+                text_delta(r#""Berlin","temp":21}"#),
+                chain_complete("object", r#"{"city":"Berlin","temp":21}"#),
+            ]
+            .concat(),
+        );
+
+        assert_eq!(accumulator.stream_type(), Some("object"));
+        assert_eq!(accumulator.text(), "");
+        assert_eq!(
+            accumulator.object(),
+            Some(&serde_json::json!({ "city": "Berlin", "temp": 21 }))
+        );
+    }
+
+    #[test]
+    fn test_accumulator_to_log_record() {
+        let chain_step = concat!(
+            "event: latitude-event\n",
+            r#"data: {"type":"chain-step","isLastStep":true,"config":{"provider":"Latitude","model":"gpt-4o-mini"},"messages":[{"role":"system","content":"Greet Ada"}],"uuid":"58e86f35-293c-4f12-a412-9915cb385850"}"#,
+            "\n\n",
+        );
+        let accumulator = accumulate(
+            &[
+                chain_step.to_owned(),
+                text_delta("Hello"),
+                text_delta(", Ada"),
+                chain_complete("text", "Hello, Ada"),
+            ]
+            .concat(),
+        );
+
+        let record = accumulator.to_log_record(
+            "Workers/Greeter",
+            serde_json::json!({ "name": "Ada" }),
+            Duration::from_millis(1250),
+        );
+
+        assert_eq!(
+            record,
+            LogRecord {
+                uuid: Some("58e86f35-293c-4f12-a412-9915cb385850".to_owned()),
+                path: "Workers/Greeter".to_owned(),
+                resolved_content: "Greet Ada".to_owned(),
+                parameters: serde_json::json!({ "name": "Ada" }),
+                response: "Hello, Ada".to_owned(),
+                usage: Some(Usage {
+                    prompt_tokens: Some(4),
+                    completion_tokens: Some(2),
+                    total_tokens: Some(6),
+                }),
+                duration: 1250,
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&record).unwrap()["resolvedContent"],
+            "Greet Ada"
+        );
+    }
+
+    #[test]
+    fn test_accumulator_applies_object_deltas() {
+        let blob = [
+            r#"data: {"type":"object-delta","patch":[{"op":"add","path":"/city","value":"Berlin"},{"op":"add","path":"/tags","value":["sunny"]}]}"#,
+            r#"data: {"type":"object-delta","patch":[{"op":"add","path":"/temp","value":21},{"op":"add","path":"/tags/-","value":"warm"}]}"#,
+        ]
+        .iter()
+        .map(|data| format!("event: provider-event\n{}\n\n", data))
+        .collect::<String>();
+
+        let accumulator = accumulate(&blob);
+
+        assert_eq!(
+            accumulator.partial_object(),
+            Some(serde_json::json!({ "city": "Berlin", "temp": 21, "tags": ["sunny", "warm"] }))
+        );
+        assert_eq!(accumulator.text(), "");
+    }
+}
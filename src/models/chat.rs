@@ -1,16 +1,24 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::error::Error;
 
-use super::message::Message;
+use super::{
+    config::RunConfig,
+    message::{Content, Message, Role},
+};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Chat {
     pub messages: Vec<Message>,
+    /// Generation settings overriding the conversation's, e.g. to switch to a stronger model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<RunConfig>,
     #[serde(skip)]
     pub conversation_id: String,
-    #[serde(skip)]
+    /// Whether the response is streamed, sent along with the messages.
+    #[serde(default)]
     pub stream: bool,
 }
 
@@ -27,11 +35,47 @@ impl Chat {
     pub fn new(messages: Vec<Message>, conversation_id: String, stream: bool) -> Self {
         Self {
             messages,
+            config: None,
             conversation_id,
             stream,
         }
     }
 
+    /// Creates a `Chat` continuing a conversation with a single message.
+    ///
+    /// The chat isn't streamed; set `stream` to stream it.
+    ///
+    /// # Arguments
+    ///
+    /// * `conversation_id` - The ID of the conversation to continue.
+    /// * `message` - The message to send.
+    ///
+    /// # Returns
+    ///
+    /// A new `Chat` instance.
+    pub fn single(conversation_id: String, message: Message) -> Self {
+        Self::new(vec![message], conversation_id, false)
+    }
+
+    /// Creates a `Chat` continuing a conversation with a single user message.
+    ///
+    /// The chat isn't streamed; set `stream` to stream it.
+    ///
+    /// # Arguments
+    ///
+    /// * `conversation_id` - The ID of the conversation to continue.
+    /// * `text` - The text of the user message.
+    ///
+    /// # Returns
+    ///
+    /// A new `Chat` instance.
+    pub fn user(conversation_id: String, text: &str) -> Self {
+        Self::single(
+            conversation_id,
+            Message::new(Role::User, vec![Content::text(text)]),
+        )
+    }
+
     /// Creates a new `ChatBuilder` instance.
     ///
     /// # Returns
@@ -47,6 +91,7 @@ impl Chat {
 /// This builder allows you to incrementally add `Message` objects to a `Chat`.
 pub struct ChatBuilder {
     messages: Vec<Message>,
+    config: Option<RunConfig>,
     conversation_id: Option<String>,
     stream: bool,
 }
@@ -60,6 +105,7 @@ impl ChatBuilder {
     pub fn new() -> Self {
         Self {
             messages: vec![],
+            config: None,
             conversation_id: None,
             stream: false,
         }
@@ -80,6 +126,104 @@ impl ChatBuilder {
         self
     }
 
+    /// Adds earlier messages to send ahead of the new ones.
+    ///
+    /// The history is placed before every other message of the chat, whether those were
+    /// added before or after this call. The gateway keeps the state of the conversation
+    /// by its ID and appends the chat's messages to it, so the history doesn't replace
+    /// what the server already holds: pass only context it doesn't have, such as turns
+    /// from another conversation, or they'll appear twice.
+    ///
+    /// # Arguments
+    ///
+    /// * `history` - The prior messages, oldest first.
+    ///
+    /// # Returns
+    ///
+    /// The builder instance with the history added.
+    pub fn with_history(mut self, history: Vec<Message>) -> Self {
+        self.messages.splice(0..0, history);
+        self
+    }
+
+    /// Adds a system message with a single text content to the `Chat` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text of the system message.
+    ///
+    /// # Returns
+    ///
+    /// The builder instance with the system message added.
+    pub fn system(self, text: &str) -> Self {
+        self.add_text_message(Role::System, text)
+    }
+
+    /// Adds a user message with a single text content to the `Chat` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text of the user message.
+    ///
+    /// # Returns
+    ///
+    /// The builder instance with the user message added.
+    pub fn user(self, text: &str) -> Self {
+        self.add_text_message(Role::User, text)
+    }
+
+    /// Adds an assistant message with a single text content to the `Chat` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text of the assistant message.
+    ///
+    /// # Returns
+    ///
+    /// The builder instance with the assistant message added.
+    pub fn assistant(self, text: &str) -> Self {
+        self.add_text_message(Role::Assistant, text)
+    }
+
+    fn add_text_message(self, role: Role, text: &str) -> Self {
+        self.add_message(Message::new(role, vec![Content::text(text)]))
+    }
+
+    /// Adds a tool message carrying the result of a tool call to the `Chat` instance.
+    ///
+    /// When the model requested several tool calls in one step, add the result of each
+    /// one; they're sent together, matched to their calls by `tool_call_id`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_call_id` - The ID of the tool call, as received in its `ToolCallEvent`.
+    /// * `tool_name` - The name of the called tool.
+    /// * `result` - The result returned by the tool.
+    ///
+    /// # Returns
+    ///
+    /// The builder instance with the tool message added.
+    pub fn tool_result(self, tool_call_id: &str, tool_name: &str, result: Value) -> Self {
+        self.add_message(Message::new(
+            Role::Tool,
+            vec![Content::tool_result(tool_call_id, tool_name, result)],
+        ))
+    }
+
+    /// Sets the generation settings used for the next turn of the conversation.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The `RunConfig` overriding the conversation's provider or model.
+    ///
+    /// # Returns
+    ///
+    /// The builder instance with the specified config.
+    pub fn config(mut self, config: RunConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
     /// Sets the conversation ID for the `Chat` instance.
     ///
     /// # Arguments
@@ -116,6 +260,7 @@ impl ChatBuilder {
     pub fn build(self) -> Result<Chat, Error> {
         Ok(Chat {
             messages: self.messages,
+            config: self.config,
             conversation_id: self
                 .conversation_id
                 .ok_or(Error::ConfigError("Conversation ID is required".to_owned()))?,
@@ -132,8 +277,6 @@ impl Default for ChatBuilder {
 
 #[cfg(test)]
 mod tests {
-    use crate::models::message::{Content, Role};
-
     use super::*;
 
     #[test]
@@ -159,9 +302,9 @@ mod tests {
 
         assert_eq!(chat.messages.len(), 2);
         assert_eq!(chat.messages[0].role, Role::User);
-        assert_eq!(chat.messages[0].content[0].text, "Hello");
+        assert_eq!(chat.messages[0].content[0].as_text(), Some("Hello"));
         assert_eq!(chat.messages[1].role, Role::Assistant);
-        assert_eq!(chat.messages[1].content[0].text, "Hi there!");
+        assert_eq!(chat.messages[1].content[0].as_text(), Some("Hi there!"));
     }
 
     #[test]
@@ -177,10 +320,7 @@ mod tests {
             .build();
 
         assert!(chat_result.is_err());
-        assert_eq!(
-            matches!(chat_result.err().unwrap(), Error::ConfigError(_)),
-            true
-        );
+        assert!(matches!(chat_result.err().unwrap(), Error::ConfigError(_)));
     }
 
     #[test]
@@ -193,8 +333,7 @@ mod tests {
 
         assert_eq!(message.role, Role::User);
         assert_eq!(message.content.len(), 1);
-        assert_eq!(message.content[0].type_field, "text");
-        assert_eq!(message.content[0].text, "How are you?");
+        assert_eq!(message.content[0], Content::text("How are you?"));
     }
 
     #[test]
@@ -204,25 +343,173 @@ mod tests {
             .build();
 
         assert!(message_result.is_err());
-        assert_eq!(
-            matches!(message_result.err().unwrap(), Error::ConfigError(_)),
-            true
-        );
+        assert!(matches!(
+            message_result.err().unwrap(),
+            Error::ConfigError(_)
+        ));
+    }
+
+    #[test]
+    fn test_chat_builder_role_shortcuts() {
+        let chat = Chat::builder()
+            .conversation_id("some-id".to_string())
+            .system("You are a comedian")
+            .user("Tell me a joke")
+            .assistant("Why did the crab never share?")
+            .build()
+            .unwrap();
+
+        let roles: Vec<Role> = chat.messages.iter().map(|m| m.role.clone()).collect();
+        assert_eq!(roles, vec![Role::System, Role::User, Role::Assistant]);
+
+        for (message, text) in chat.messages.iter().zip([
+            "You are a comedian",
+            "Tell me a joke",
+            "Why did the crab never share?",
+        ]) {
+            assert_eq!(message.content.len(), 1);
+            assert_eq!(message.content[0], Content::text(text));
+        }
     }
 
     #[test]
     fn test_chat_new_function_with_conversation_id() {
         let messages = vec![Message::new(
             Role::User,
-            vec![Content {
-                type_field: "text".to_string(),
-                text: "Hello from new".to_string(),
-            }],
+            vec![Content::text("Hello from new")],
         )];
         let chat = Chat::new(messages, "some-id".to_owned(), false);
 
         assert_eq!(chat.messages.len(), 1);
         assert_eq!(chat.messages[0].role, Role::User);
-        assert_eq!(chat.messages[0].content[0].text, "Hello from new");
+        assert_eq!(
+            chat.messages[0].content[0].as_text(),
+            Some("Hello from new")
+        );
+    }
+
+    #[test]
+    fn test_chat_single_turn_constructors() {
+        let chat = Chat::user("some-id".to_owned(), "Tell me more");
+
+        assert_eq!(chat.conversation_id, "some-id");
+        assert!(!chat.stream);
+        assert_eq!(chat.messages.len(), 1);
+        assert_eq!(chat.messages[0].role, Role::User);
+        assert_eq!(
+            chat.messages[0].content,
+            vec![Content::text("Tell me more")]
+        );
+
+        let chat = Chat::single(
+            "some-id".to_owned(),
+            Message::new(Role::Assistant, vec![Content::text("Noted")]),
+        );
+
+        assert_eq!(chat.messages.len(), 1);
+        assert_eq!(chat.messages[0].role, Role::Assistant);
+        assert!(!chat.stream);
+    }
+
+    #[test]
+    fn test_chat_serializes_config_override() {
+        let chat = Chat::builder()
+            .conversation_id("some-id".to_string())
+            .user("Try harder")
+            .config(
+                RunConfig::builder()
+                    .provider("OpenAI")
+                    .model("gpt-4o")
+                    .build(),
+            )
+            .build()
+            .unwrap();
+
+        let body = serde_json::to_value(&chat).unwrap();
+
+        assert_eq!(
+            body["config"],
+            serde_json::json!({ "provider": "OpenAI", "model": "gpt-4o" })
+        );
+
+        let chat = Chat::builder()
+            .conversation_id("some-id".to_string())
+            .user("Hello")
+            .build()
+            .unwrap();
+
+        assert!(serde_json::to_value(&chat).unwrap().get("config").is_none());
+    }
+
+    #[test]
+    fn test_chat_history_precedes_new_messages() {
+        let chat = Chat::builder()
+            .conversation_id("some-id".to_string())
+            .user("And in Paris?")
+            .with_history(vec![
+                Message::new(Role::User, vec![Content::text("Weather in Rome?")]),
+                Message::new(Role::Assistant, vec![Content::text("Sunny, 25°C")]),
+            ])
+            .build()
+            .unwrap();
+
+        let body = serde_json::to_value(&chat).unwrap();
+        let texts: Vec<&str> = body["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|message| message["content"][0]["text"].as_str().unwrap())
+            .collect();
+
+        assert_eq!(
+            texts,
+            vec!["Weather in Rome?", "Sunny, 25°C", "And in Paris?"]
+        );
+        assert_eq!(body["messages"][1]["role"], "assistant");
+    }
+
+    #[test]
+    fn test_chat_serializes_parallel_tool_results() {
+        let chat = Chat::builder()
+            .conversation_id("some-id".to_string())
+            .tool_result("call-1", "get_weather", serde_json::json!({ "temp": 21 }))
+            .tool_result("call-2", "get_time", serde_json::json!("12:00"))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&chat).unwrap(),
+            serde_json::json!({
+                "messages": [
+                    {
+                        "role": "tool",
+                        "content": [{
+                            "type": "tool-result",
+                            "toolCallId": "call-1",
+                            "toolName": "get_weather",
+                            "result": { "temp": 21 },
+                            "isError": false
+                        }]
+                    },
+                    {
+                        "role": "tool",
+                        "content": [{
+                            "type": "tool-result",
+                            "toolCallId": "call-2",
+                            "toolName": "get_time",
+                            "result": "12:00",
+                            "isError": false
+                        }]
+                    }
+                ],
+                "stream": false
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<Chat>(serde_json::to_value(&chat).unwrap())
+                .unwrap()
+                .messages,
+            chat.messages
+        );
     }
 }
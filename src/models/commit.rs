@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// Commit holds the metadata of a project commit, i.e. a version of its documents.
+///
+/// Documents and logs reference the commit they belong to by its `id`, see
+/// `Document::commit_id` and `LogResponse::commit_id`.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Commit {
+    pub id: i64,
+    pub uuid: String,
+    pub title: String,
+    pub created_at: String,
+}
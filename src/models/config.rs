@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents the generation settings sent along with a run.
+///
+/// Unset fields are omitted from the request, so the prompt's own configuration applies.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunConfig {
+    /// The provider used for the generation, overriding the prompt's provider.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// The model used for the generation, overriding the prompt's model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// The maximum number of steps the gateway executes for a chained or agent prompt.
+    ///
+    /// Once the limit is reached the chain finishes early, which bounds the cost of a run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_steps: Option<u32>,
+    /// The seed used for sampling, to pin generations, e.g. in tests.
+    ///
+    /// Whether the same seed reproduces the same output depends on the provider; some
+    /// ignore it and others only make a best effort.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Whether the provider should return the log-probabilities of the generated tokens.
+    ///
+    /// When supported, they're reported in the `logprobs` of the response and of the
+    /// `step-finish` events.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<bool>,
+}
+
+impl RunConfig {
+    pub fn builder() -> RunConfigBuilder {
+        RunConfigBuilder::default()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RunConfigBuilder {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub max_steps: Option<u32>,
+    pub seed: Option<u64>,
+    pub logprobs: Option<bool>,
+}
+
+impl RunConfigBuilder {
+    /// Sets the provider used for the generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The name of the provider configured in Latitude.
+    pub fn provider(mut self, provider: &str) -> Self {
+        self.provider = Some(provider.to_owned());
+        self
+    }
+
+    /// Sets the model used for the generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - The model of the provider, e.g. `gpt-4o`.
+    pub fn model(mut self, model: &str) -> Self {
+        self.model = Some(model.to_owned());
+        self
+    }
+
+    /// Sets the maximum number of steps executed for a chained prompt.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_steps` - The maximum number of chain steps.
+    pub fn max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    /// Sets the seed used for sampling.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed; reproducibility depends on the provider.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets whether token log-probabilities are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `logprobs` - Whether to request the log-probabilities.
+    pub fn logprobs(mut self, logprobs: bool) -> Self {
+        self.logprobs = Some(logprobs);
+        self
+    }
+
+    /// Builds the `RunConfig` instance with the specified settings.
+    ///
+    /// # Returns
+    ///
+    /// A `RunConfig` instance.
+    pub fn build(self) -> RunConfig {
+        RunConfig {
+            provider: self.provider,
+            model: self.model,
+            max_steps: self.max_steps,
+            seed: self.seed,
+            logprobs: self.logprobs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::document::RunDocument;
+    use serde_json::json;
+
+    #[test]
+    fn test_run_document_serializes_max_steps() {
+        let document = RunDocument::<()>::builder()
+            .path("Agents/Researcher".to_owned())
+            .config(RunConfig::builder().max_steps(3).build())
+            .build();
+
+        assert_eq!(
+            serde_json::to_value(&document).unwrap(),
+            json!({
+                "path": "Agents/Researcher",
+                "stream": false,
+                "config": { "maxSteps": 3 }
+            })
+        );
+    }
+
+    #[test]
+    fn test_run_document_serializes_seed() {
+        let document = RunDocument::<()>::builder()
+            .path("Workers/EmotionAnalyzer".to_owned())
+            .config(RunConfig::builder().seed(42).build())
+            .build();
+
+        assert_eq!(
+            serde_json::to_value(&document).unwrap()["config"],
+            json!({ "seed": 42 })
+        );
+    }
+
+    #[test]
+    fn test_run_document_serializes_logprobs() {
+        let document = RunDocument::<()>::builder()
+            .path("Workers/Classifier".to_owned())
+            .config(RunConfig::builder().logprobs(true).build())
+            .build();
+
+        assert_eq!(
+            serde_json::to_value(&document).unwrap()["config"],
+            json!({ "logprobs": true })
+        );
+    }
+
+    #[test]
+    fn test_run_document_omits_unset_config() {
+        let document = RunDocument::<()>::builder()
+            .path("Agents/Researcher".to_owned())
+            .build();
+
+        assert_eq!(
+            serde_json::to_value(&document).unwrap(),
+            json!({ "path": "Agents/Researcher", "stream": false })
+        );
+    }
+}
@@ -1,9 +1,15 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::{collections::BTreeMap, marker::PhantomData};
 
 use crate::error::Error;
 
-use super::options::Options;
+use super::{
+    config::RunConfig,
+    event::{self, TokenLogprob, ToolCall},
+    options::Options,
+    prompt::Prompt,
+};
 
 /// `RunDocument` represents a document request with specific parameters.
 /// The `parameters` field is optional, allowing for requests without parameters.
@@ -16,8 +22,18 @@ where
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parameters: Option<T>,
     pub stream: bool,
+    /// Generation settings for the run, such as the maximum number of chain steps.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<RunConfig>,
     #[serde(skip)]
     pub options: Option<Options>,
+    /// Extra query parameters appended to the run URL (e.g. gateway feature flags).
+    #[serde(skip)]
+    pub query_params: BTreeMap<String, String>,
+    /// JSON schema the response text is validated against before it is returned.
+    #[cfg(feature = "schema-validation")]
+    #[serde(skip)]
+    pub output_schema: Option<Value>,
 }
 
 impl<T> RunDocument<T>
@@ -41,55 +57,109 @@ where
             path,
             parameters: parameters.or_else(|| Some(T::default())),
             stream,
+            config: None,
             options,
+            query_params: BTreeMap::new(),
+            #[cfg(feature = "schema-validation")]
+            output_schema: None,
         }
     }
+}
 
-    pub fn builder() -> RunDocumentBuilder<T> {
+impl<T> RunDocument<T>
+where
+    T: Serialize,
+{
+    /// Creates a new `RunDocumentBuilder`.
+    ///
+    /// Unlike `new`, the builder doesn't require `T: Default`: without parameters, the
+    /// document is sent without any.
+    ///
+    /// # Returns
+    ///
+    /// A `RunDocumentBuilder` whose path is yet to be set.
+    pub fn builder() -> RunDocumentBuilder<T, NoPath> {
         RunDocumentBuilder::default()
     }
 }
 
+/// Builder state of a `RunDocumentBuilder` whose path hasn't been set yet.
+pub struct NoPath;
+
+/// Builder state of a `RunDocumentBuilder` whose path has been set.
+pub struct WithPath;
+
 /// A builder for creating `RunDocument` instances.
 ///
 /// This builder allows you to set optional fields, such as `parameters`, before building
-/// the `RunDocument` instance. By default, if `parameters` is not set, it will use `T::default()`.
-pub struct RunDocumentBuilder<T>
+/// the `RunDocument` instance. If `parameters` is not set, the document is sent without
+/// parameters, so `T` doesn't need to implement `Default`.
+///
+/// The path is required, and the builder tracks whether it has been set in its type, so
+/// `build` can only be called once it is:
+///
+/// ```compile_fail
+/// use latitude_sdk::models::document::RunDocument;
+///
+/// let document = RunDocument::<()>::builder().stream().build();
+/// ```
+///
+/// Use `try_build` when the path is only known at runtime.
+pub struct RunDocumentBuilder<T, P = WithPath>
 where
-    T: Serialize + Default,
+    T: Serialize,
 {
     pub path: Option<String>,
     pub parameters: Option<T>,
     pub stream: Option<bool>,
+    pub config: Option<RunConfig>,
     pub options: Option<Options>,
+    pub query_params: BTreeMap<String, String>,
+    #[cfg(feature = "schema-validation")]
+    pub output_schema: Option<Value>,
+    state: PhantomData<P>,
 }
 
-impl<T> Default for RunDocumentBuilder<T>
+impl<T> Default for RunDocumentBuilder<T, NoPath>
 where
-    T: Serialize + Default,
+    T: Serialize,
 {
     fn default() -> Self {
         Self {
             path: None,
             parameters: None,
             stream: None,
+            config: None,
             options: None,
+            query_params: BTreeMap::new(),
+            #[cfg(feature = "schema-validation")]
+            output_schema: None,
+            state: PhantomData,
         }
     }
 }
 
-impl<T> RunDocumentBuilder<T>
+impl<T, P> RunDocumentBuilder<T, P>
 where
-    T: Serialize + Default,
+    T: Serialize,
 {
     /// Sets the path for the `RunDocument`.
     ///
     /// # Arguments
     ///
     /// * `path` - The path of the document to be run.
-    pub fn path(mut self, path: String) -> Self {
-        self.path = Some(path);
-        self
+    pub fn path(self, path: String) -> RunDocumentBuilder<T, WithPath> {
+        RunDocumentBuilder {
+            path: Some(path),
+            parameters: self.parameters,
+            stream: self.stream,
+            config: self.config,
+            options: self.options,
+            query_params: self.query_params,
+            #[cfg(feature = "schema-validation")]
+            output_schema: self.output_schema,
+            state: PhantomData,
+        }
     }
 
     /// Sets the optional parameters for the `RunDocument`.
@@ -112,6 +182,15 @@ where
         self
     }
 
+    /// Sets the generation settings for the `RunDocument`.
+    ///
+    /// # Arguments
+    /// * `config` - The `RunConfig` sent along with the run.
+    pub fn config(mut self, config: RunConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
     /// Sets the Options for the `RunDocument`.
     ///
     /// # Arguments
@@ -121,46 +200,170 @@ where
         self
     }
 
-    /// Builds the `RunDocument` instance with the specified parameters.
+    /// Adds an extra query parameter to the run URL.
     ///
-    /// If `parameters` is not provided, it will default to `T::default()`.
+    /// Some gateway features (e.g. `debug=true`) are controlled via query parameters.
+    /// Keys and values are URL-encoded when the request is sent.
+    ///
+    /// # Arguments
+    /// * `key` - The query parameter name.
+    /// * `value` - The query parameter value.
+    pub fn query_param(mut self, key: &str, value: &str) -> Self {
+        self.query_params.insert(key.to_owned(), value.to_owned());
+        self
+    }
+
+    /// Sets the JSON schema the response text must conform to.
+    ///
+    /// When set, the text of a non-streaming response is parsed as JSON and validated
+    /// against the schema, returning `Error::ResponseFormatError` if it doesn't conform.
+    ///
+    /// # Arguments
+    /// * `schema` - The JSON schema declared for the prompt's output.
+    #[cfg(feature = "schema-validation")]
+    pub fn output_schema(mut self, schema: Value) -> Self {
+        self.output_schema = Some(schema);
+        self
+    }
+
+    /// Builds the `RunDocument` instance, checking at runtime that the path is set.
+    ///
+    /// If `parameters` is not provided, none are sent.
     /// If `stream` is not provided, it will default to `false`.
     ///
     /// # Returns
     ///
-    /// A `RunDocument` instance.
-    pub fn build(self) -> Result<RunDocument<T>, Error> {
+    /// A `RunDocument` instance, or `Error::ConfigError` if the path isn't set.
+    pub fn try_build(self) -> Result<RunDocument<T>, Error> {
         Ok(RunDocument {
             path: self
                 .path
                 .ok_or(Error::ConfigError("Path is required".to_string()))?,
             parameters: self.parameters,
             stream: self.stream.unwrap_or(false),
+            config: self.config,
             options: self.options,
+            query_params: self.query_params,
+            #[cfg(feature = "schema-validation")]
+            output_schema: self.output_schema,
         })
     }
 }
 
+impl<T> RunDocumentBuilder<T, WithPath>
+where
+    T: Serialize,
+{
+    /// Builds the `RunDocument` instance with the specified parameters.
+    ///
+    /// If `parameters` is not provided, none are sent.
+    /// If `stream` is not provided, it will default to `false`.
+    ///
+    /// # Returns
+    ///
+    /// A `RunDocument` instance.
+    pub fn build(self) -> RunDocument<T> {
+        self.try_build()
+            .expect("The path of a `WithPath` builder is always set")
+    }
+}
+
 /// RunResponse represents the response returned after executing a document.
-#[derive(Debug, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Deserialize, PartialEq)]
 pub struct RunResponse {
     pub uuid: String,
     pub response: ResponseDetail,
+    /// The ID of the request from its `X-Request-Id` header, to quote in support tickets.
+    #[serde(skip)]
+    pub request_id: Option<String>,
+}
+
+impl RunResponse {
+    /// Converts the response into the shape of a streamed `chain-complete` response.
+    ///
+    /// This lets code handling `ChainComplete::response` handle JSON responses as well.
+    /// The conversation UUID becomes the document log UUID, and the stream type, which
+    /// JSON responses don't carry, is `None`.
+    ///
+    /// # Returns
+    /// The equivalent `event::Response`.
+    pub fn to_event_response(&self) -> event::Response {
+        let usage = &self.response.usage;
+
+        event::Response {
+            stream_type: None,
+            document_log_uuid: Some(self.uuid.clone()),
+            text: self.response.text.clone(),
+            tool_calls: (!self.response.tool_calls.is_empty())
+                .then(|| self.response.tool_calls.clone()),
+            usage: event::Usage {
+                prompt_tokens: usage.prompt_tokens,
+                completion_tokens: usage.completion_tokens,
+                total_tokens: usage.total_tokens,
+            },
+        }
+    }
+
+    /// Validates the response text against a JSON schema.
+    ///
+    /// # Arguments
+    /// * `schema` - The JSON schema the response text is expected to conform to.
+    ///
+    /// # Returns
+    /// `Ok(())` if the text is valid JSON matching the schema, otherwise
+    /// `Error::ResponseFormatError` describing the validation errors.
+    #[cfg(feature = "schema-validation")]
+    pub fn validate_schema(&self, schema: &Value) -> Result<(), Error> {
+        let validator = jsonschema::validator_for(schema)
+            .map_err(|e| Error::ConfigError(format!("Invalid output schema: {}", e)))?;
+
+        let instance: Value = serde_json::from_str(&self.response.text).map_err(|e| {
+            Error::ResponseFormatError(format!("Response text is not valid JSON: {}", e))
+        })?;
+
+        let errors: Vec<String> = validator
+            .iter_errors(&instance)
+            .map(|e| e.to_string())
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::ResponseFormatError(format!(
+                "Response does not match output schema: {}",
+                errors.join("; ")
+            )))
+        }
+    }
 }
 
 /// ResponseDetail provides detailed response data including generated text and token usage.
-#[derive(Debug, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Deserialize, PartialEq)]
 pub struct ResponseDetail {
     pub text: String,
     pub usage: UsageDetail,
+    /// The log-probabilities of the generated tokens, when requested and supported.
+    pub logprobs: Option<Vec<TokenLogprob>>,
+    /// The tool calls the run is awaiting the results of, if it stopped to call tools.
+    #[serde(default, rename = "toolCalls")]
+    pub tool_calls: Vec<ToolCall>,
+}
+
+impl ResponseDetail {
+    /// Returns whether the run stopped to call tools, and awaits their results.
+    ///
+    /// The results are sent with `Client::chat`, after which the run goes on.
+    pub fn awaits_tool_results(&self) -> bool {
+        !self.tool_calls.is_empty()
+    }
 }
 
 /// UsageDetail contains detailed usage statistics, such as token counts.
 #[derive(Debug, Deserialize, Eq, PartialEq)]
 pub struct UsageDetail {
-    pub prompt_tokens: Option<usize>,
-    pub completion_tokens: Option<usize>,
-    pub total_tokens: Option<usize>,
+    pub prompt_tokens: Option<u64>,
+    pub completion_tokens: Option<u64>,
+    pub total_tokens: Option<u64>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -179,6 +382,33 @@ pub struct Document {
     pub merged_at: Option<String>,
     pub project_id: i64,
     pub config: Config,
+    /// The inputs declared by the prompt, empty if the API doesn't report them.
+    #[serde(default)]
+    pub parameters: Vec<DocumentParameter>,
+}
+
+impl Document {
+    /// Parses the content of the document into its PromptL structure.
+    ///
+    /// # Returns
+    ///
+    /// The `Prompt` holding the front-matter config, the body and the names of the
+    /// `{{param}}` placeholders, or `Error::ConfigError` if the front matter is invalid.
+    pub fn parse_prompt(&self) -> Result<Prompt, Error> {
+        Prompt::parse(&self.content)
+    }
+
+    /// Compares the semantically meaningful fields of two documents.
+    ///
+    /// Unlike `==`, the timestamps and database IDs are ignored, so this answers whether
+    /// a document has changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The document to compare with.
+    pub fn content_eq(&self, other: &Document) -> bool {
+        self.path == other.path && self.content == other.content && self.config == other.config
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -187,3 +417,150 @@ pub struct Config {
     pub provider: String,
     pub model: String,
 }
+
+/// DocumentParameter describes an input declared by a prompt, e.g. to build a form for it.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentParameter {
+    pub name: String,
+    /// The type of the parameter, such as `text`, `image` or `file`.
+    #[serde(default)]
+    pub r#type: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    /// The value used when the parameter isn't given.
+    #[serde(default)]
+    pub default: Option<Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_document_builder_with_path() {
+        let document = RunDocument::<()>::builder()
+            .stream()
+            .path("Workers/EmotionAnalyzer".to_owned())
+            .build();
+
+        assert_eq!(document.path, "Workers/EmotionAnalyzer");
+        assert!(document.stream);
+    }
+
+    #[test]
+    fn test_run_document_builder_without_default_params() {
+        #[derive(Debug, Serialize)]
+        struct NonDefaultParams {
+            user_message: String,
+        }
+
+        let document = RunDocument::<NonDefaultParams>::builder()
+            .path("Workers/EmotionAnalyzer".to_owned())
+            .parameters(NonDefaultParams {
+                user_message: "Hello".to_owned(),
+            })
+            .build();
+
+        assert_eq!(
+            serde_json::to_value(&document).unwrap()["parameters"],
+            serde_json::json!({ "user_message": "Hello" })
+        );
+
+        let document = RunDocument::<NonDefaultParams>::builder()
+            .path("Workers/EmotionAnalyzer".to_owned())
+            .build();
+
+        assert!(document.parameters.is_none());
+        assert!(serde_json::to_value(&document)
+            .unwrap()
+            .get("parameters")
+            .is_none());
+    }
+
+    #[test]
+    fn test_run_document_try_build_without_path() {
+        let result = RunDocument::<()>::builder().stream().try_build();
+
+        assert!(matches!(result, Err(Error::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_document_content_eq_ignores_timestamps() {
+        let document = Document {
+            path: "Workers/EmotionAnalyzer".to_owned(),
+            content: "Classify {{ message }}".to_owned(),
+            config: Config {
+                provider: "OpenAI".to_owned(),
+                model: "gpt-4o-mini".to_owned(),
+            },
+            created_at: "2024-11-01T00:00:00Z".to_owned(),
+            updated_at: "2024-11-01T00:00:00Z".to_owned(),
+            ..Default::default()
+        };
+
+        let touched = Document {
+            id: 2,
+            commit_id: 7,
+            updated_at: "2024-11-02T00:00:00Z".to_owned(),
+            merged_at: Some("2024-11-02T00:00:00Z".to_owned()),
+            ..document.clone()
+        };
+        assert_ne!(document, touched);
+        assert!(document.content_eq(&touched));
+
+        let edited = Document {
+            content: "Classify {{ message }} briefly".to_owned(),
+            ..document.clone()
+        };
+        assert!(!document.content_eq(&edited));
+
+        let moved = Document {
+            path: "Workers/SentimentAnalyzer".to_owned(),
+            ..document.clone()
+        };
+        assert!(!document.content_eq(&moved));
+
+        let reconfigured = Document {
+            config: Config {
+                model: "gpt-4o".to_owned(),
+                ..document.config.clone()
+            },
+            ..document.clone()
+        };
+        assert!(!document.content_eq(&reconfigured));
+    }
+
+    #[test]
+    fn test_run_response_to_event_response() {
+        let response: RunResponse = serde_json::from_value(serde_json::json!({
+            "uuid": "conversation-uuid",
+            "response": {
+                "text": "Hello!",
+                "usage": {
+                    "prompt_tokens": 10,
+                    "completion_tokens": 20,
+                    "total_tokens": 30
+                }
+            }
+        }))
+        .unwrap();
+
+        let event_response = response.to_event_response();
+
+        assert_eq!(event_response.text, "Hello!");
+        assert_eq!(
+            event_response.usage,
+            event::Usage {
+                prompt_tokens: Some(10),
+                completion_tokens: Some(20),
+                total_tokens: Some(30)
+            }
+        );
+        assert_eq!(
+            event_response.document_log_uuid.as_deref(),
+            Some("conversation-uuid")
+        );
+        assert_eq!(event_response.tool_calls, None);
+    }
+}
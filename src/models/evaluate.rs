@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EvaluationResponse {
@@ -10,3 +11,30 @@ pub struct EvaluationResponse {
 pub struct Evaluation {
     pub evaluation_uuids: Vec<Option<String>>,
 }
+
+/// Status of an evaluation result, which may still be computed asynchronously after `eval`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EvaluationStatus {
+    Pending,
+    Completed,
+    Failed,
+}
+
+/// EvaluationResult holds the outcome of a single evaluation run against a conversation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluationResult {
+    pub uuid: String,
+    pub evaluation_uuid: String,
+    pub status: EvaluationStatus,
+    pub result: Option<Value>,
+    pub reason: Option<String>,
+}
+
+impl EvaluationResult {
+    /// Returns `true` once the evaluation is no longer pending.
+    pub fn is_ready(&self) -> bool {
+        self.status != EvaluationStatus::Pending
+    }
+}
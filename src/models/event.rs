@@ -1,12 +1,12 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 use uuid::Uuid;
 
-use super::message::Role;
+use super::{message::Role, pricing::ModelPricing};
 
 /// Event enumerates the possible event types, which may either be latitude events or provider events.
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum Event {
     LatitudeEvent(LatitudeEvent),
@@ -14,6 +14,83 @@ pub enum Event {
     UnknownEvent,
 }
 
+impl Event {
+    /// Returns the text carried by this event, if it's a provider `text-delta` event.
+    pub fn text_delta(&self) -> Option<&str> {
+        match self {
+            Event::ProviderEvent(ProviderEvent {
+                event_type: ProviderEventType::TextDelta(delta),
+            }) => Some(&delta.text_delta),
+            _ => None,
+        }
+    }
+
+    /// Returns the token usage carried by this event, if it reports any.
+    ///
+    /// Usage is reported by provider `step-finish`/`finish` events and by Latitude
+    /// `chain-step-complete`/`chain-complete` events.
+    pub fn usage(&self) -> Option<&Usage> {
+        match self {
+            Event::LatitudeEvent(event) => match &event.event_type {
+                LatitudeEventType::ChainStepComplete(complete) => Some(&complete.response.usage),
+                LatitudeEventType::ChainComplete(complete) => Some(&complete.response.usage),
+                LatitudeEventType::ChainStep(_) => None,
+            },
+            Event::ProviderEvent(event) => match &event.event_type {
+                ProviderEventType::StepFinish(finish) => Some(&finish.usage),
+                ProviderEventType::Finish(finish) => Some(&finish.usage),
+                _ => None,
+            },
+            Event::UnknownEvent => None,
+        }
+    }
+
+    /// Returns the provider-specific metadata of a provider `step-finish`/`finish` event.
+    ///
+    /// Providers attach different fields to finish events, e.g. OpenAI's
+    /// `system_fingerprint` or Anthropic's `stop_sequence`; the fields this crate doesn't
+    /// model are preserved here.
+    ///
+    /// # Returns
+    /// The extra fields by name, or `None` for any other event.
+    pub fn finish_metadata(&self) -> Option<&Map<String, Value>> {
+        match self {
+            Event::ProviderEvent(event) => match &event.event_type {
+                ProviderEventType::StepFinish(finish) => Some(&finish.extra),
+                ProviderEventType::Finish(finish) => Some(&finish.extra),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns whether the chain continues after this event rather than completing.
+    ///
+    /// This is the case for provider `step-finish`/`finish` events flagged `isContinued`,
+    /// and for those finishing with `FinishReason::ToolCalls`: the model requested tool
+    /// calls and is waiting for their results, even when the server doesn't flag the step
+    /// as continued because the tools are run by the caller. Agent loops should then submit
+    /// the tool results through `chat` instead of treating the stream as complete.
+    ///
+    /// # Returns
+    /// `true` if a continuation is expected, `false` for any other event.
+    pub fn expects_continuation(&self) -> bool {
+        let Event::ProviderEvent(event) = self else {
+            return false;
+        };
+
+        match &event.event_type {
+            ProviderEventType::StepFinish(finish) => {
+                finish.is_continued || finish.finish_reason == FinishReason::ToolCalls
+            }
+            ProviderEventType::Finish(finish) => {
+                finish.is_continued == Some(true) || finish.finish_reason == FinishReason::ToolCalls
+            }
+            _ => false,
+        }
+    }
+}
+
 /// LatitudeEvent represents an event from Latitude, detailing event type and associated data.
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -45,8 +122,8 @@ pub struct ChainStep {
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ChainStepComplete {
-    response: Response,
-    uuid: String,
+    pub response: Response,
+    pub uuid: String,
 }
 
 /// ChainComplete represents a completed chain with response and configuration details.
@@ -101,26 +178,54 @@ pub struct ToolCall {
 }
 
 /// Usage provides the token usage statistics for a given response.
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+///
+/// Providers may report partial usage, so a count that's missing or `null` is `None`
+/// rather than failing the event.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Usage {
-    pub prompt_tokens: usize,
-    pub completion_tokens: usize,
-    pub total_tokens: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_tokens: Option<u64>,
+}
+
+impl Usage {
+    /// Estimates the cost of the usage for a model's pricing.
+    ///
+    /// Token counts that weren't reported count as zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `pricing` - The prices of the model's prompt and completion tokens.
+    ///
+    /// # Returns
+    ///
+    /// The cost, in the currency of the pricing.
+    pub fn estimate_cost(&self, pricing: &ModelPricing) -> f64 {
+        let tokens = |count: Option<u64>| count.unwrap_or_default() as f64 / 1000.0;
+
+        tokens(self.prompt_tokens) * pricing.prompt_per_1k
+            + tokens(self.completion_tokens) * pricing.completion_per_1k
+    }
 }
 
 /// ProviderEvent represents an event from the provider, with details about the event type.
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ProviderEvent {
     #[serde(rename = "type", flatten)]
     pub event_type: ProviderEventType,
 }
 
 /// ProviderEventType enumerates different provider event types (e.g., text deltas, tool results).
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", rename_all = "kebab-case")]
 pub enum ProviderEventType {
     TextDelta(TextDelta),
+    ObjectDelta(ObjectDelta),
+    ToolCallDelta(ToolCallDelta),
     ToolCall(ToolCallEvent),
     ToolResult(ToolResultEvent),
     StepFinish(StepFinish),
@@ -135,6 +240,28 @@ pub struct TextDelta {
     pub text_delta: String,
 }
 
+/// ObjectDelta carries an incremental update of a streamed object as a JSON patch.
+///
+/// Applying the `patch` operations (RFC 6902, e.g. `{"op":"add","path":"/city","value":"Berlin"}`)
+/// of every delta in order yields the evolving object.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectDelta {
+    pub patch: Value,
+}
+
+/// ToolCallDelta carries a fragment of a tool call's arguments while they're being streamed.
+///
+/// Concatenating the `args_text_delta` fragments of a `tool_call_id` yields the JSON
+/// arguments of the completed call.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCallDelta {
+    pub tool_call_id: String,
+    pub tool_name: String,
+    pub args_text_delta: String,
+}
+
 /// ToolCallEvent represents an event indicating a call to an external tool.
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -154,16 +281,40 @@ pub struct ToolResultEvent {
 }
 
 /// StepFinish represents the completion of a step, with details on usage and the response.
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct StepFinish {
     pub finish_reason: FinishReason,
     pub usage: Usage,
     pub response: ProviderResponse,
     pub is_continued: bool,
+    /// The log-probabilities of the generated tokens, when requested and supported.
+    pub logprobs: Option<Vec<TokenLogprob>>,
+    /// Provider-specific metadata, e.g. OpenAI's `system_fingerprint`.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// TokenLogprob holds the log-probability of a generated token and its top alternatives.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenLogprob {
+    pub token: String,
+    pub logprob: f64,
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+/// TopLogprob holds the log-probability of an alternative token.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TopLogprob {
+    pub token: String,
+    pub logprob: f64,
 }
 
 /// FinishReason enumerates the reasons why a step finished.
+///
+/// Unrecognized reasons deserialize into `Unknown` rather than failing the event.
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum FinishReason {
@@ -173,6 +324,7 @@ pub enum FinishReason {
     ToolCalls,
     Error,
     Other,
+    #[serde(other)]
     Unknown,
 }
 
@@ -180,10 +332,13 @@ pub enum FinishReason {
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ProviderFinish {
-    pub finish_reason: String,
+    pub finish_reason: FinishReason,
     pub usage: Usage,
     pub response: ProviderResponse,
     pub is_continued: Option<bool>,
+    /// Provider-specific metadata, e.g. OpenAI's `system_fingerprint`.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 /// ProviderResponse contains metadata for the provider's response, such as ID and timestamp.
@@ -202,3 +357,142 @@ pub struct ErrorEvent {
     pub error_message: String,
     pub error_code: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn finish_event(finish_reason: &str) -> Value {
+        json!({
+            "type": "finish",
+            "finishReason": finish_reason,
+            "usage": {
+                "promptTokens": 10,
+                "completionTokens": 20,
+                "totalTokens": 30
+            },
+            "response": {
+                "id": "resp-1",
+                "timestamp": "2024-11-01T00:00:00Z",
+                "modelId": "gpt-4o-mini"
+            },
+            "isContinued": false
+        })
+    }
+
+    #[test]
+    fn test_partial_usage() {
+        let mut finish = finish_event("stop");
+        finish["usage"] = json!({ "promptTokens": 10, "completionTokens": null });
+
+        let event: ProviderEvent = serde_json::from_value(finish).expect("Failed to parse event");
+
+        match event.event_type {
+            ProviderEventType::Finish(finish) => assert_eq!(
+                finish.usage,
+                Usage {
+                    prompt_tokens: Some(10),
+                    completion_tokens: None,
+                    total_tokens: None,
+                }
+            ),
+            other => panic!("Expected finish event, got {:?}", other),
+        }
+
+        let usage: Usage =
+            serde_json::from_value(json!({ "totalTokens": 5_000_000_000u64 })).unwrap();
+        assert_eq!(usage.total_tokens, Some(5_000_000_000));
+    }
+
+    #[test]
+    fn test_provider_finish_known_reason() {
+        let event: ProviderEvent =
+            serde_json::from_value(finish_event("tool-calls")).expect("Failed to parse event");
+
+        match event.event_type {
+            ProviderEventType::Finish(finish) => {
+                assert_eq!(finish.finish_reason, FinishReason::ToolCalls)
+            }
+            other => panic!("Expected finish event, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_step_finish_logprobs() {
+        let mut step_finish = finish_event("stop");
+        step_finish["type"] = json!("step-finish");
+        step_finish["logprobs"] = json!([
+            {
+                "token": "Happy",
+                "logprob": -0.05,
+                "topLogprobs": [
+                    { "token": "Happy", "logprob": -0.05 },
+                    { "token": "Sad", "logprob": -3.2 }
+                ]
+            }
+        ]);
+
+        let event: ProviderEvent =
+            serde_json::from_value(step_finish).expect("Failed to parse event");
+
+        match event.event_type {
+            ProviderEventType::StepFinish(finish) => {
+                let logprobs = finish.logprobs.expect("Expected logprobs");
+                assert_eq!(logprobs[0].token, "Happy");
+                assert_eq!(logprobs[0].logprob, -0.05);
+                assert_eq!(logprobs[0].top_logprobs[1].token, "Sad");
+            }
+            other => panic!("Expected step-finish event, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_finish_metadata_preserved() {
+        let mut finish = finish_event("stop");
+        finish["systemFingerprint"] = json!("fp_44709d6fcb");
+
+        let event = Event::ProviderEvent(
+            serde_json::from_value(finish.clone()).expect("Failed to parse event"),
+        );
+
+        let metadata = event.finish_metadata().expect("Expected finish metadata");
+        assert_eq!(metadata.len(), 1);
+        assert_eq!(metadata["systemFingerprint"], "fp_44709d6fcb");
+
+        let Event::ProviderEvent(provider_event) = event else {
+            unreachable!();
+        };
+        assert_eq!(serde_json::to_value(provider_event).unwrap(), finish);
+    }
+
+    #[test]
+    fn test_expects_continuation() {
+        let event = |value: Value| {
+            Event::ProviderEvent(serde_json::from_value(value).expect("Failed to parse event"))
+        };
+
+        assert!(event(finish_event("tool-calls")).expects_continuation());
+        assert!(!event(finish_event("stop")).expects_continuation());
+
+        let mut step_finish = finish_event("stop");
+        step_finish["type"] = json!("step-finish");
+        step_finish["isContinued"] = json!(true);
+        assert!(event(step_finish).expects_continuation());
+
+        assert!(!event(json!({ "type": "text-delta", "textDelta": "Hi" })).expects_continuation());
+    }
+
+    #[test]
+    fn test_provider_finish_unknown_reason() {
+        let event: ProviderEvent = serde_json::from_value(finish_event("max-steps-reached"))
+            .expect("Failed to parse event");
+
+        match event.event_type {
+            ProviderEventType::Finish(finish) => {
+                assert_eq!(finish.finish_reason, FinishReason::Unknown)
+            }
+            other => panic!("Expected finish event, got {:?}", other),
+        }
+    }
+}
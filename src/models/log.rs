@@ -1,4 +1,4 @@
-use super::{message::Message, options::Options};
+use super::{event::Usage, message::Message, options::Options};
 use crate::error::Error;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -171,3 +171,135 @@ pub struct LogResponse {
     pub created_at: String,
     pub updated_at: String,
 }
+
+impl LogResponse {
+    /// Compares the semantically meaningful fields of two logs.
+    ///
+    /// Unlike `==`, the timestamps, IDs and duration are ignored, so logs of the same
+    /// document content and parameters compare equal.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The log to compare with.
+    pub fn content_eq(&self, other: &LogResponse) -> bool {
+        self.document_uuid == other.document_uuid
+            && self.content_hash == other.content_hash
+            && self.resolved_content == other.resolved_content
+            && self.parameters == other.parameters
+    }
+}
+
+/// A record of a streamed run shaped like a document log, ready to persist in your own store.
+///
+/// See `StreamAccumulator::to_log_record`.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRecord {
+    /// The UUID of the conversation, if the stream identified it.
+    pub uuid: Option<String>,
+    pub path: String,
+    /// The prompt the run started with, its parameters resolved.
+    pub resolved_content: String,
+    pub parameters: Value,
+    /// The text of the response.
+    pub response: String,
+    pub usage: Option<Usage>,
+    /// The duration of the run, in milliseconds.
+    pub duration: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::message::{Content, Role};
+    use serde_json::json;
+
+    #[test]
+    fn test_log_serializes_image_content() {
+        let log = Log::builder()
+            .path("Vision/Describe")
+            .add_message(Message::new(
+                Role::User,
+                vec![
+                    Content::text("What's in this picture?"),
+                    Content::Image {
+                        image: "https://example.com/cat.png".to_owned(),
+                    },
+                ],
+            ))
+            .response("A cat")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&log).unwrap(),
+            json!({
+                "path": "Vision/Describe",
+                "messages": [{
+                    "role": "user",
+                    "content": [
+                        { "type": "text", "text": "What's in this picture?" },
+                        { "type": "image", "image": "https://example.com/cat.png" }
+                    ]
+                }],
+                "response": "A cat"
+            })
+        );
+    }
+
+    #[test]
+    fn test_log_response_content_eq_ignores_timestamps() {
+        let log = LogResponse {
+            id: 1,
+            uuid: "log-uuid".to_owned(),
+            document_uuid: "doc-uuid".to_owned(),
+            resolved_content: "Classify {{ message }}".to_owned(),
+            content_hash: "hash".to_owned(),
+            parameters: json!({ "message": "I love it" }),
+            duration: json!(120),
+            created_at: "2024-11-01T00:00:00Z".to_owned(),
+            updated_at: "2024-11-01T00:00:00Z".to_owned(),
+            ..Default::default()
+        };
+
+        let rerun = LogResponse {
+            id: 2,
+            uuid: "other-log-uuid".to_owned(),
+            duration: json!(95),
+            created_at: "2024-11-02T00:00:00Z".to_owned(),
+            updated_at: "2024-11-02T00:00:00Z".to_owned(),
+            ..log.clone()
+        };
+        assert_ne!(log, rerun);
+        assert!(log.content_eq(&rerun));
+
+        let other_input = LogResponse {
+            parameters: json!({ "message": "I hate it" }),
+            ..log.clone()
+        };
+        assert!(!log.content_eq(&other_input));
+    }
+
+    #[test]
+    fn test_log_response_with_multimodal_parameters() {
+        let response: LogResponse = serde_json::from_value(json!({
+            "id": 1,
+            "uuid": "log-uuid",
+            "documentUuid": "doc-uuid",
+            "commitId": 2,
+            "resolvedContent": "Describe {{picture}}",
+            "contentHash": "hash",
+            "parameters": {
+                "picture": { "type": "image", "image": "data:image/png;base64,iVBORw0KGgo=" }
+            },
+            "customIdentifier": null,
+            "duration": 120,
+            "source": "api",
+            "createdAt": "2024-11-01T00:00:00Z",
+            "updatedAt": "2024-11-01T00:00:00Z"
+        }))
+        .expect("Failed to parse log response");
+
+        assert_eq!(response.parameters["picture"]["type"], "image");
+    }
+}
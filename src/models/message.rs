@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::error::Error;
 
@@ -38,6 +39,7 @@ impl Message {
 pub struct MessageBuilder {
     role: Option<Role>,
     content: Vec<Content>,
+    unsupported_content_type: Option<String>,
 }
 
 impl MessageBuilder {
@@ -50,6 +52,7 @@ impl MessageBuilder {
         Self {
             role: None,
             content: vec![],
+            unsupported_content_type: None,
         }
     }
 
@@ -67,30 +70,96 @@ impl MessageBuilder {
         self
     }
 
-    /// Adds content to the `Message`.
+    /// Adds text content to the `Message`.
     ///
     /// # Arguments
     ///
-    /// * `type_field` - The type of the content (e.g., "text").
+    /// * `type_field` - The type of the content, which must be `"text"`; other kinds of
+    ///   content are added with their own methods, e.g. `add_image`.
     /// * `text` - The actual text content.
     ///
     /// # Returns
     ///
-    /// The builder instance with the new content added.
+    /// The builder instance with the new content added. With any other `type_field`,
+    /// `build` fails with `Error::ConfigError`.
     pub fn add_content(mut self, type_field: &str, text: &str) -> Self {
-        self.content.push(Content {
-            type_field: type_field.to_owned(),
-            text: text.to_owned(),
+        if type_field == "text" {
+            self.content.push(Content::text(text));
+        } else {
+            self.unsupported_content_type
+                .get_or_insert_with(|| type_field.to_owned());
+        }
+        self
+    }
+
+    /// Adds an image to the `Message`.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image, as a URL or a base64 data URL.
+    ///
+    /// # Returns
+    ///
+    /// The builder instance with the image added.
+    pub fn add_image(mut self, image: &str) -> Self {
+        self.content.push(Content::Image {
+            image: image.to_owned(),
+        });
+        self
+    }
+
+    /// Adds a file to the `Message`, e.g. a PDF.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The file, as a URL or a base64 data URL.
+    /// * `mime_type` - The MIME type of the file, e.g. `"application/pdf"`.
+    ///
+    /// # Returns
+    ///
+    /// The builder instance with the file added.
+    pub fn add_file(mut self, file: &str, mime_type: &str) -> Self {
+        self.content.push(Content::File {
+            file: file.to_owned(),
+            mime_type: mime_type.to_owned(),
         });
         self
     }
 
+    /// Adds the result of a tool call to the `Message`, for messages with the `Tool` role.
+    ///
+    /// This continues a conversation after a `ProviderEventType::ToolCall`, with the ID
+    /// received in its `ToolCallEvent`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_call_id` - The ID of the tool call this is the result of.
+    /// * `tool_name` - The name of the called tool.
+    /// * `result` - The result returned by the tool.
+    ///
+    /// # Returns
+    ///
+    /// The builder instance with the tool result added.
+    pub fn add_tool_result(mut self, tool_call_id: &str, tool_name: &str, result: Value) -> Self {
+        self.content
+            .push(Content::tool_result(tool_call_id, tool_name, result));
+        self
+    }
+
     /// Builds the `Message` instance.
     ///
     /// # Returns
     ///
-    /// A `Message` instance with the specified role and content.
+    /// A `Message` instance with the specified role and content, or `Error::ConfigError` if
+    /// the role is missing or text content was added with a type other than `"text"`.
     pub fn build(self) -> Result<Message, Error> {
+        if let Some(type_field) = self.unsupported_content_type {
+            return Err(Error::ConfigError(format!(
+                "Content of type {:?} can't be added as text",
+                type_field
+            )));
+        }
+
         Ok(Message {
             role: self
                 .role
@@ -106,19 +175,191 @@ impl Default for MessageBuilder {
     }
 }
 
+/// Content is a part of a message's content, discriminated by its `type`.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Content {
-    #[serde(rename = "type")]
-    pub type_field: String,
-    pub text: String,
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Content {
+    /// Plain text, serialized as `{ "type": "text", "text": ... }`.
+    Text { text: String },
+    /// An image, given as a URL or base64 data URL.
+    Image { image: String },
+    /// A file, given as a URL or base64 data URL, along with its MIME type.
+    #[serde(rename_all = "camelCase")]
+    File { file: String, mime_type: String },
+    /// The result of a tool call, sent back to the model in a `Role::Tool` message.
+    ///
+    /// The `tool_call_id` matches the `ToolCallEvent` the result answers, so the results
+    /// of parallel tool calls can be sent together.
+    #[serde(rename_all = "camelCase")]
+    ToolResult {
+        tool_call_id: String,
+        tool_name: String,
+        result: Value,
+        #[serde(default)]
+        is_error: bool,
+    },
+}
+
+impl Content {
+    /// Creates text content.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text.
+    pub fn text(text: &str) -> Self {
+        Content::Text {
+            text: text.to_owned(),
+        }
+    }
+
+    /// Creates the content carrying the result of a tool call.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_call_id` - The ID of the tool call this is the result of.
+    /// * `tool_name` - The name of the called tool.
+    /// * `result` - The result returned by the tool.
+    pub fn tool_result(tool_call_id: &str, tool_name: &str, result: Value) -> Self {
+        Content::ToolResult {
+            tool_call_id: tool_call_id.to_owned(),
+            tool_name: tool_name.to_owned(),
+            result,
+            is_error: false,
+        }
+    }
+
+    /// Returns the text of text content.
+    ///
+    /// # Returns
+    ///
+    /// The text, or `None` for other kinds of content.
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Content::Text { text } => Some(text),
+            _ => None,
+        }
+    }
 }
 
 /// Role enumerates the different roles involved in message exchange (e.g., System, Assistant, User).
+///
+/// Roles other than the known ones (e.g. "developer") are preserved in `Role::Other`
+/// instead of failing deserialization.
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
-#[serde(rename_all = "lowercase")]
+#[serde(from = "String", into = "String")]
 pub enum Role {
     System,
     Assistant,
     User,
+    /// The role of messages carrying tool results.
+    Tool,
+    Other(String),
+}
+
+impl From<String> for Role {
+    fn from(role: String) -> Self {
+        match role.as_str() {
+            "system" => Role::System,
+            "assistant" => Role::Assistant,
+            "user" => Role::User,
+            "tool" => Role::Tool,
+            _ => Role::Other(role),
+        }
+    }
+}
+
+impl From<Role> for String {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::System => "system".to_owned(),
+            Role::Assistant => "assistant".to_owned(),
+            Role::User => "user".to_owned(),
+            Role::Tool => "tool".to_owned(),
+            Role::Other(role) => role,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_unknown_falls_back_to_other() {
+        let role: Role = serde_json::from_str(r#""developer""#).expect("Failed to parse role");
+
+        assert_eq!(role, Role::Other("developer".to_owned()));
+        assert_eq!(serde_json::to_string(&role).unwrap(), r#""developer""#);
+    }
+
+    #[test]
+    fn test_role_known_serializes_lowercase() {
+        let role: Role = serde_json::from_str(r#""assistant""#).expect("Failed to parse role");
+
+        assert_eq!(role, Role::Assistant);
+        assert_eq!(serde_json::to_string(&Role::System).unwrap(), r#""system""#);
+        assert_eq!(serde_json::to_string(&Role::Tool).unwrap(), r#""tool""#);
+    }
+
+    #[test]
+    fn test_mixed_content_message_serializes() {
+        let message = Message::builder()
+            .role(Role::User)
+            .add_content("text", "Summarize these")
+            .add_image("https://example.com/chart.png")
+            .add_file("data:application/pdf;base64,JVBERi0x", "application/pdf")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            serde_json::json!({
+                "role": "user",
+                "content": [
+                    { "type": "text", "text": "Summarize these" },
+                    { "type": "image", "image": "https://example.com/chart.png" },
+                    {
+                        "type": "file",
+                        "file": "data:application/pdf;base64,JVBERi0x",
+                        "mimeType": "application/pdf"
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_add_content_rejects_non_text_type() {
+        let result = Message::builder()
+            .role(Role::User)
+            .add_content("image", "https://example.com/chart.png")
+            .build();
+
+        assert!(matches!(result, Err(Error::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_tool_message_round_trips() {
+        let message = Message::builder()
+            .role(Role::Tool)
+            .add_tool_result("call-1", "get_weather", serde_json::json!("21°C and sunny"))
+            .build()
+            .unwrap();
+
+        let value = serde_json::to_value(&message).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "role": "tool",
+                "content": [{
+                    "type": "tool-result",
+                    "toolCallId": "call-1",
+                    "toolName": "get_weather",
+                    "result": "21°C and sunny",
+                    "isError": false
+                }]
+            })
+        );
+        assert_eq!(serde_json::from_value::<Message>(value).unwrap(), message);
+    }
 }
@@ -1,8 +1,15 @@
+pub mod accumulator;
 pub mod chat;
+pub mod commit;
+pub mod config;
 pub mod document;
 pub mod evaluate;
 pub mod event;
 pub mod log;
 pub mod message;
 pub mod options;
+pub mod prelude;
+pub mod pricing;
+pub mod prompt;
+#[cfg(feature = "client")]
 pub mod response;
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
 /// Represents the configuration settings
@@ -5,6 +7,15 @@ use serde::{Deserialize, Serialize};
 pub struct Options {
     pub version_id: Option<String>,
     pub project_id: Option<u64>,
+    /// How often a rate-limited or failed request is retried, overriding the client's default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+    /// The deadline of the request, overriding the client's default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<Duration>,
+    /// The workspace the request is scoped to, overriding the client's default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
 }
 
 impl Options {
@@ -18,18 +29,64 @@ impl Options {
         Self {
             version_id,
             project_id,
+            max_retries: None,
+            timeout: None,
+            workspace_id: None,
         }
     }
 
     pub fn builder() -> OptionsBuilder {
         OptionsBuilder::default()
     }
+
+    /// Merges these options with a set of defaults.
+    ///
+    /// Fields set on `self` take precedence; unset fields fall back to `defaults`.
+    ///
+    /// # Arguments
+    ///
+    /// * `defaults` - The options used for fields not set on `self`.
+    pub fn merge(self, defaults: &Options) -> Options {
+        Options {
+            version_id: self.version_id.or_else(|| defaults.version_id.clone()),
+            project_id: self.project_id.or(defaults.project_id),
+            max_retries: self.max_retries.or(defaults.max_retries),
+            timeout: self.timeout.or(defaults.timeout),
+            workspace_id: self.workspace_id.or_else(|| defaults.workspace_id.clone()),
+        }
+    }
+
+    /// Derives a stable cache key for a document path under these options.
+    ///
+    /// A missing version resolves to `"live"`, matching how requests resolve it, so
+    /// logically-equal option sets always produce the same key.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the document the key refers to.
+    pub fn cache_key(&self, path: &str) -> String {
+        let project_id = self
+            .project_id
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| "-".to_owned());
+        let version_id = self.version_id.as_deref().unwrap_or("live");
+
+        format!(
+            "{}/{}/{}",
+            project_id,
+            version_id,
+            path.trim_start_matches('/')
+        )
+    }
 }
 
 #[derive(Debug, Default)]
 pub struct OptionsBuilder {
     pub version_id: Option<String>,
     pub project_id: Option<u64>,
+    pub max_retries: Option<u32>,
+    pub timeout: Option<Duration>,
+    pub workspace_id: Option<String>,
 }
 
 impl OptionsBuilder {
@@ -53,6 +110,43 @@ impl OptionsBuilder {
         self
     }
 
+    /// Sets how often a rate-limited or failed request is retried, overriding the client's
+    /// default.
+    ///
+    /// Latency-sensitive calls can set this to `0` to fail fast, while batch calls
+    /// retry more often.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_retries` - The number of retries after the first attempt.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    /// Sets the deadline of the request, overriding the client's default.
+    ///
+    /// The deadline spans from sending the request until its response has been read,
+    /// which for a streamed run includes the whole stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long the request may take.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the workspace the request is scoped to, overriding the client's default.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - The ID of the workspace.
+    pub fn workspace_id(mut self, workspace_id: String) -> Self {
+        self.workspace_id = Some(workspace_id);
+        self
+    }
+
     /// Builds the `Options` instance with the specified version ID and project ID.
     ///
     /// # Returns
@@ -62,6 +156,51 @@ impl OptionsBuilder {
         Options {
             version_id: self.version_id,
             project_id: self.project_id,
+            max_retries: self.max_retries,
+            timeout: self.timeout,
+            workspace_id: self.workspace_id,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_prefers_own_fields() {
+        let defaults = Options::new(Some("pinned".to_owned()), Some(1));
+        let merged = Options::builder().project_id(2).build().merge(&defaults);
+
+        assert_eq!(merged, Options::new(Some("pinned".to_owned()), Some(2)));
+    }
+
+    #[test]
+    fn test_cache_key_equal_for_logically_equal_options() {
+        let implicit = Options::new(None, Some(12345));
+        let explicit = Options::builder()
+            .project_id(12345)
+            .version_id("live".to_owned())
+            .build();
+
+        assert_eq!(
+            implicit.cache_key("Workers/Joke"),
+            explicit.cache_key("/Workers/Joke")
+        );
+    }
+
+    #[test]
+    fn test_cache_key_differs_by_version() {
+        let live = Options::new(Some("live".to_owned()), Some(12345));
+        let pinned = Options::new(Some("version-uuid".to_owned()), Some(12345));
+
+        assert_ne!(
+            live.cache_key("Workers/Joke"),
+            pinned.cache_key("Workers/Joke")
+        );
+        assert_eq!(
+            pinned.cache_key("Workers/Joke"),
+            "12345/version-uuid/Workers/Joke"
+        );
+    }
+}
@@ -0,0 +1,49 @@
+//! Re-exports the request-side builders and their inputs.
+//!
+//! `use latitude_sdk::models::prelude::*` covers constructing every request; the types
+//! of the responses stay in their respective modules.
+//!
+//! # Example
+//!
+//! ```
+//! use latitude_sdk::models::prelude::*;
+//!
+//! let options = Options::builder().project_id(123).build();
+//!
+//! let document = RunDocument::<()>::builder()
+//!     .path("Workers/EmotionAnalyzer".to_owned())
+//!     .options(options.clone())
+//!     .build();
+//!
+//! let message = Message::builder()
+//!     .role(Role::User)
+//!     .add_content("text", "Hello, world!")
+//!     .build()
+//!     .unwrap();
+//!
+//! let log = Log::builder()
+//!     .path("Workers/EmotionAnalyzer")
+//!     .add_message(message.clone())
+//!     .response("Hi!")
+//!     .options(options)
+//!     .build()
+//!     .unwrap();
+//!
+//! let chat = Chat::builder()
+//!     .conversation_id("conversation-uuid".to_owned())
+//!     .add_message(message)
+//!     .stream()
+//!     .build()
+//!     .unwrap();
+//!
+//! assert_eq!(document.path, log.path);
+//! assert_eq!(chat.messages[0].content[0].as_text(), Some("Hello, world!"));
+//! ```
+
+pub use super::chat::{Chat, ChatBuilder};
+pub use super::config::{RunConfig, RunConfigBuilder};
+pub use super::document::{RunDocument, RunDocumentBuilder};
+pub use super::evaluate::Evaluation;
+pub use super::log::{Log, LogBuilder};
+pub use super::message::{Content, Message, MessageBuilder, Role};
+pub use super::options::{Options, OptionsBuilder};
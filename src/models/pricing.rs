@@ -0,0 +1,113 @@
+/// ModelPricing holds the price of a model's tokens, used to estimate the cost of a usage.
+///
+/// Prices are in the currency of the caller's choosing, per 1000 tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    /// The price of 1000 prompt tokens.
+    pub prompt_per_1k: f64,
+    /// The price of 1000 completion tokens.
+    pub completion_per_1k: f64,
+}
+
+/// The list prices of common models in US dollars per 1000 tokens, matched by prefix so
+/// dated snapshots such as `gpt-4o-2024-08-06` are priced like their model. Longer names
+/// come first so they win over the names they start with.
+#[cfg(feature = "pricing")]
+const MODEL_PRICES: &[(&str, ModelPricing)] = &[
+    ("gpt-4o-mini", ModelPricing::new(0.00015, 0.0006)),
+    ("gpt-4o", ModelPricing::new(0.0025, 0.01)),
+    ("gpt-4-turbo", ModelPricing::new(0.01, 0.03)),
+    ("gpt-3.5-turbo", ModelPricing::new(0.0005, 0.0015)),
+    ("claude-3-5-sonnet", ModelPricing::new(0.003, 0.015)),
+    ("claude-3-5-haiku", ModelPricing::new(0.0008, 0.004)),
+    ("claude-3-opus", ModelPricing::new(0.015, 0.075)),
+];
+
+impl ModelPricing {
+    /// Creates a new `ModelPricing`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt_per_1k` - The price of 1000 prompt tokens.
+    /// * `completion_per_1k` - The price of 1000 completion tokens.
+    pub const fn new(prompt_per_1k: f64, completion_per_1k: f64) -> Self {
+        Self {
+            prompt_per_1k,
+            completion_per_1k,
+        }
+    }
+
+    /// Looks up the built-in list price of a common model, in US dollars.
+    ///
+    /// The table is a snapshot and isn't updated when providers change their prices, so
+    /// pass your own `ModelPricing` when exact figures matter.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - The name of the model, e.g. `gpt-4o-mini`.
+    ///
+    /// # Returns
+    ///
+    /// The pricing of the model, or `None` if it isn't in the table.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::models::{event::Usage, pricing::ModelPricing};
+    ///
+    /// let usage = Usage {
+    ///     prompt_tokens: Some(2000),
+    ///     completion_tokens: Some(1000),
+    ///     total_tokens: Some(3000),
+    /// };
+    ///
+    /// let pricing = ModelPricing::for_model("gpt-4o-2024-08-06").unwrap();
+    /// assert!((usage.estimate_cost(&pricing) - 0.015).abs() < 1e-9);
+    /// ```
+    #[cfg(feature = "pricing")]
+    pub fn for_model(model: &str) -> Option<Self> {
+        MODEL_PRICES
+            .iter()
+            .find(|(name, _)| model.starts_with(name))
+            .map(|(_, pricing)| *pricing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::event::Usage;
+
+    #[test]
+    fn test_estimate_cost() {
+        let usage = Usage {
+            prompt_tokens: Some(1500),
+            completion_tokens: Some(500),
+            total_tokens: Some(2000),
+        };
+        let pricing = ModelPricing::new(0.01, 0.03);
+
+        assert!((usage.estimate_cost(&pricing) - 0.03).abs() < 1e-9);
+
+        let partial = Usage {
+            prompt_tokens: Some(1000),
+            ..Default::default()
+        };
+
+        assert!((partial.estimate_cost(&pricing) - 0.01).abs() < 1e-9);
+    }
+
+    #[cfg(feature = "pricing")]
+    #[test]
+    fn test_for_model() {
+        assert_eq!(
+            ModelPricing::for_model("gpt-4o-mini-2024-07-18"),
+            Some(ModelPricing::new(0.00015, 0.0006))
+        );
+        assert_eq!(
+            ModelPricing::for_model("gpt-4o"),
+            Some(ModelPricing::new(0.0025, 0.01))
+        );
+        assert_eq!(ModelPricing::for_model("unknown-model"), None);
+    }
+}
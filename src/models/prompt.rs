@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::Error;
+
+/// The delimiter of a PromptL front-matter config block.
+const FRONT_MATTER_DELIMITER: &str = "---";
+
+/// Control keywords of PromptL that appear between braces without being parameters.
+const KEYWORDS: [&str; 3] = ["else", "endif", "endfor"];
+
+/// Prompt represents the structure of a PromptL document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Prompt {
+    /// The config declared in the front matter, or an empty object if there's none.
+    pub config: Value,
+    /// The prompt body following the front matter.
+    pub body: String,
+    /// The names of the `{{param}}` placeholders of the body, in order of first appearance.
+    pub parameters: Vec<String>,
+}
+
+impl Prompt {
+    /// Parses PromptL content into its front-matter config, body and parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The content of the document.
+    ///
+    /// # Returns
+    ///
+    /// The parsed `Prompt`, or `Error::ConfigError` if the front matter is unterminated or
+    /// isn't valid YAML.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use latitude_sdk::models::prompt::Prompt;
+    ///
+    /// let prompt = Prompt::parse("---\nmodel: gpt-4o\n---\nHello {{ name }}!").unwrap();
+    ///
+    /// assert_eq!(prompt.config["model"], "gpt-4o");
+    /// assert_eq!(prompt.body, "Hello {{ name }}!");
+    /// assert_eq!(prompt.parameters, vec!["name"]);
+    /// ```
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        let (config, body) = split_front_matter(content)?;
+
+        let config = match config {
+            Some(config) => serde_norway::from_str::<Value>(config)
+                .map_err(|e| Error::ConfigError(format!("Invalid prompt config: {}", e)))?,
+            None => Value::Null,
+        };
+
+        Ok(Self {
+            config: match config {
+                Value::Null => Value::Object(Map::new()),
+                config => config,
+            },
+            body: body.to_owned(),
+            parameters: parameters(body),
+        })
+    }
+}
+
+/// Splits the front-matter config block, if any, from the body.
+fn split_front_matter(content: &str) -> Result<(Option<&str>, &str), Error> {
+    let trimmed = content.trim_start();
+
+    let Some(rest) = trimmed.strip_prefix(FRONT_MATTER_DELIMITER) else {
+        return Ok((None, content));
+    };
+    let Some(rest) = rest.strip_prefix('\n').or(rest.strip_prefix("\r\n")) else {
+        return Ok((None, content));
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == FRONT_MATTER_DELIMITER {
+            let body = &rest[offset + line.len()..];
+            return Ok((Some(&rest[..offset]), body));
+        }
+        offset += line.len();
+    }
+
+    Err(Error::ConfigError(
+        "Unterminated prompt front matter".to_owned(),
+    ))
+}
+
+/// Collects the names of the `{{param}}` placeholders of a prompt body.
+fn parameters(body: &str) -> Vec<String> {
+    let mut parameters: Vec<String> = vec![];
+    let mut rest = body;
+
+    while let Some(start) = rest.find("{{") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find("}}") else {
+            break;
+        };
+
+        let name = rest[..end].trim();
+        rest = &rest[end + 2..];
+
+        if is_identifier(name)
+            && !KEYWORDS.contains(&name)
+            && !parameters.iter().any(|parameter| parameter == name)
+        {
+            parameters.push(name.to_owned());
+        }
+    }
+
+    parameters
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::document::Document;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_prompt_with_front_matter() {
+        let document = Document {
+            content: concat!(
+                "---\n",
+                "provider: OpenAI\n",
+                "model: gpt-4o-mini\n",
+                "temperature: 0.2\n",
+                "---\n",
+                "Classify the emotion of {{ message }} written by {{author}}.\n",
+                "{{ if strict }}Answer with one word, {{ author }}.{{ endif }}\n",
+            )
+            .to_owned(),
+            ..Default::default()
+        };
+
+        let prompt = document.parse_prompt().expect("Failed to parse prompt");
+
+        assert_eq!(
+            prompt.config,
+            json!({ "provider": "OpenAI", "model": "gpt-4o-mini", "temperature": 0.2 })
+        );
+        assert!(prompt.body.starts_with("Classify the emotion"));
+        assert_eq!(prompt.parameters, vec!["message", "author"]);
+    }
+
+    #[test]
+    fn test_parse_prompt_without_front_matter() {
+        let prompt = Prompt::parse("Hello {{name}}").expect("Failed to parse prompt");
+
+        assert_eq!(prompt.config, json!({}));
+        assert_eq!(prompt.body, "Hello {{name}}");
+        assert_eq!(prompt.parameters, vec!["name"]);
+    }
+
+    #[test]
+    fn test_parse_prompt_unterminated_front_matter() {
+        let result = Prompt::parse("---\nmodel: gpt-4o\nHello");
+
+        assert!(matches!(result, Err(Error::ConfigError(_))));
+    }
+}
@@ -1,7 +1,30 @@
-use crate::models::event::Event;
-use tokio::sync::mpsc::Receiver;
+use crate::{
+    error::Error,
+    models::event::{Event, Usage},
+    Client,
+};
+use futures_util::{ready, Stream, StreamExt};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    sync::{
+        broadcast,
+        mpsc::{self, error::TrySendError},
+        watch,
+    },
+    task::AbortHandle,
+};
+use tokio_stream::wrappers::BroadcastStream;
 
-use super::document::RunResponse;
+use super::{accumulator::StreamAccumulator, document::RunResponse};
 
 /// Enum to represent the response type from the `run` method.
 #[derive(Debug)]
@@ -9,5 +32,460 @@ pub enum Response {
     /// JSON response when `stream` is set to `false`.
     Json(RunResponse),
     /// Streaming response when `stream` is set to `true`.
-    Stream(Receiver<Event>),
+    Stream(EventStream),
+}
+
+impl Response {
+    /// Collects the response into a `RunResponse`, whether it's streamed or not.
+    ///
+    /// A streamed response is drained, assembling its text deltas and final usage, which
+    /// suits callers streaming for responsiveness but wanting the same result as the JSON
+    /// path in the end.
+    ///
+    /// Events whose payload fails to parse are skipped, as the stream goes on after them.
+    ///
+    /// # Returns
+    /// The `RunResponse`, the error that ended the stream, or `Error::ResponseFormatError`
+    /// if the stream ended before identifying the conversation.
+    pub async fn collect(self) -> Result<RunResponse, Error> {
+        let mut events = match self {
+            Response::Json(response) => return Ok(response),
+            Response::Stream(events) => events,
+        };
+
+        let mut accumulator = StreamAccumulator::new();
+        while let Some(event) = events.next().await {
+            match event {
+                Ok(event) => accumulator.push(&event),
+                Err(Error::SerializationError(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        accumulator.run_response().ok_or_else(|| {
+            Error::ResponseFormatError("The stream ended without a response".to_owned())
+        })
+    }
+}
+
+/// The timing breakdown of a request, measured from when it was first sent.
+///
+/// Connection setup isn't exposed by the HTTP client, so `request_sent` covers DNS
+/// resolution, connecting and waiting for the server to answer, along with any retries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timing {
+    /// The time until the response headers were received.
+    pub request_sent: Duration,
+    /// The time until the first event was received, for streamed responses.
+    pub first_event: Option<Duration>,
+    /// The time until the response was fully received.
+    pub completed: Duration,
+}
+
+/// A streamed event along with its raw `data:` payload, when raw events are captured, or
+/// the error met while receiving the stream.
+pub(crate) type StreamItem = Result<(Event, Option<String>), SharedError>;
+
+/// An error met while receiving a stream, taken out by the consumer once received.
+///
+/// Items are cloned, as `SlowConsumerPolicy::DropOldest` requires, and its channel keeps
+/// a copy of every item it sent. The error is thus moved out of the shared slot instead
+/// of being unwrapped, which only succeeds for the last copy.
+#[derive(Debug, Clone)]
+pub(crate) struct SharedError(Arc<Mutex<Option<Error>>>);
+
+impl SharedError {
+    /// Takes the error out of the slot.
+    ///
+    /// A stream has a single consumer, which receives every item once, so the error is
+    /// always there.
+    fn take(&self) -> Error {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .unwrap_or_else(|| Error::Other("The stream error was already received".to_owned()))
+    }
+}
+
+impl From<Error> for SharedError {
+    fn from(error: Error) -> Self {
+        SharedError(Arc::new(Mutex::new(Some(error))))
+    }
+}
+
+/// SlowConsumerPolicy decides what happens when the consumer of a stream falls behind.
+///
+/// Streamed events are buffered until the consumer receives them. Once the buffer is
+/// full, the policy applies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SlowConsumerPolicy {
+    /// Waits for the consumer to catch up, pausing the read of the response. A consumer
+    /// that stalls for long may cause the server to time out.
+    #[default]
+    Block,
+    /// Keeps reading the response, dropping the oldest buffered events to make room.
+    DropOldest,
+    /// Stops the stream. Once the buffered events have been received, the stream yields
+    /// an `Error::Other` and ends; `EventStream::overflowed` reports why.
+    Error,
+}
+
+/// The sending half of the channel feeding an `EventStream`.
+#[derive(Debug)]
+pub(crate) enum EventSender {
+    Block(mpsc::Sender<StreamItem>),
+    DropOldest(broadcast::Sender<StreamItem>),
+    Error(mpsc::Sender<StreamItem>, Arc<AtomicBool>),
+}
+
+impl EventSender {
+    /// Sends an event to the consumer, applying the slow consumer policy.
+    ///
+    /// # Returns
+    /// `true` if the stream goes on, or `false` once the consumer is gone or overflowed.
+    pub(crate) async fn send(&self, item: StreamItem) -> bool {
+        match self {
+            EventSender::Block(sender) => sender.send(item).await.is_ok(),
+            EventSender::DropOldest(sender) => sender.send(item).is_ok(),
+            EventSender::Error(sender, overflowed) => match sender.try_send(item) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    overflowed.store(true, Ordering::Relaxed);
+                    false
+                }
+                Err(TrySendError::Closed(_)) => false,
+            },
+        }
+    }
+}
+
+/// The receiving half of the channel feeding an `EventStream`.
+#[derive(Debug)]
+pub(crate) enum EventReceiver {
+    Block(mpsc::Receiver<StreamItem>),
+    DropOldest(BroadcastStream<StreamItem>),
+    Error(mpsc::Receiver<StreamItem>, Arc<AtomicBool>),
+}
+
+impl From<mpsc::Receiver<StreamItem>> for EventReceiver {
+    fn from(receiver: mpsc::Receiver<StreamItem>) -> Self {
+        EventReceiver::Block(receiver)
+    }
+}
+
+/// Creates the channel feeding an `EventStream`, buffering `capacity` events before the
+/// slow consumer policy applies.
+pub(crate) fn channel(policy: SlowConsumerPolicy, capacity: usize) -> (EventSender, EventReceiver) {
+    match policy {
+        SlowConsumerPolicy::Block => {
+            let (sender, receiver) = mpsc::channel(capacity);
+            (EventSender::Block(sender), EventReceiver::Block(receiver))
+        }
+        SlowConsumerPolicy::DropOldest => {
+            let (sender, receiver) = broadcast::channel(capacity);
+            (
+                EventSender::DropOldest(sender),
+                EventReceiver::DropOldest(BroadcastStream::new(receiver)),
+            )
+        }
+        SlowConsumerPolicy::Error => {
+            let (sender, receiver) = mpsc::channel(capacity);
+            let overflowed = Arc::new(AtomicBool::new(false));
+            (
+                EventSender::Error(sender, overflowed.clone()),
+                EventReceiver::Error(receiver, overflowed),
+            )
+        }
+    }
+}
+
+/// Cancels the run generating a stream on the server, once its conversation is known.
+pub(crate) struct RunCanceller {
+    client: Client,
+    conversation: Arc<Mutex<Option<String>>>,
+}
+
+impl RunCanceller {
+    /// Creates a `RunCanceller` for the conversation the stream task records in
+    /// `conversation` when it's announced.
+    pub(crate) fn new(client: Client, conversation: Arc<Mutex<Option<String>>>) -> Self {
+        Self {
+            client,
+            conversation,
+        }
+    }
+}
+
+impl std::fmt::Debug for RunCanceller {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RunCanceller")
+            .field("conversation", &self.conversation)
+            .finish_non_exhaustive()
+    }
+}
+
+/// EventStream yields the events of a streamed response as they're received.
+///
+/// Events can be received with `recv`, or through the `Stream` implementation, which
+/// composes with stream combinators such as `map`, `filter` or `timeout`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use latitude_sdk::{models::document::RunDocument, models::response::Response, Client};
+/// use tokio_stream::StreamExt;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), latitude_sdk::error::Error> {
+///     let client = Client::builder("your_api_key".into()).project_id(123).build()?;
+///
+///     let document = RunDocument::<()>::builder()
+///         .path("Workers/EmotionAnalyzer".to_owned())
+///         .stream()
+///         .build();
+///
+///     if let Ok(Response::Stream(stream)) = client.run(document).await {
+///         let mut deltas = stream.filter_map(|event| event.ok()?.text_delta().map(str::to_owned));
+///
+///         while let Some(delta) = deltas.next().await {
+///             print!("{}", delta);
+///         }
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct EventStream {
+    receiver: EventReceiver,
+    raw: Option<String>,
+    text: Arc<Mutex<String>>,
+    usage: watch::Receiver<Option<Usage>>,
+    stream_id: Option<String>,
+    canceller: Option<Arc<RunCanceller>>,
+    task: AbortHandle,
+    aborted: bool,
+}
+
+impl EventStream {
+    /// Creates an `EventStream` from the channel fed by the background `task`.
+    ///
+    /// The task appends the text deltas it receives to `text`, including those of events
+    /// the consumer hasn't received yet, and sets the final usage on `usage` once known.
+    pub(crate) fn new(
+        receiver: impl Into<EventReceiver>,
+        text: Arc<Mutex<String>>,
+        usage: watch::Receiver<Option<Usage>>,
+        task: AbortHandle,
+    ) -> Self {
+        Self {
+            receiver: receiver.into(),
+            raw: None,
+            text,
+            usage,
+            stream_id: None,
+            canceller: None,
+            task,
+            aborted: false,
+        }
+    }
+
+    /// Sets the ID the gateway assigned to the stream.
+    pub(crate) fn with_stream_id(mut self, stream_id: Option<String>) -> Self {
+        self.stream_id = stream_id;
+        self
+    }
+
+    /// Sets how the run generating the stream is cancelled on the server.
+    pub(crate) fn with_canceller(mut self, canceller: Option<Arc<RunCanceller>>) -> Self {
+        self.canceller = canceller;
+        self
+    }
+
+    /// Returns how the run generating the stream is cancelled on the server.
+    pub(crate) fn canceller(&self) -> Option<Arc<RunCanceller>> {
+        self.canceller.clone()
+    }
+
+    /// Returns the ID the gateway assigned to the stream, from its `X-Stream-Id` header.
+    ///
+    /// The ID identifies the stream on the server, e.g. to correlate it with server-side
+    /// logs. The client doesn't reconnect streams, so it never sends the ID back itself.
+    ///
+    /// # Returns
+    /// The stream ID, or `None` if the gateway didn't assign one.
+    pub fn stream_id(&self) -> Option<&str> {
+        self.stream_id.as_deref()
+    }
+
+    /// Returns the buffer the text deltas of the stream are appended to.
+    pub(crate) fn text_buffer(&self) -> Arc<Mutex<String>> {
+        self.text.clone()
+    }
+
+    /// Returns the receiver of the final usage of the stream.
+    pub(crate) fn usage_receiver(&self) -> watch::Receiver<Option<Usage>> {
+        self.usage.clone()
+    }
+
+    /// Returns a future resolving to the total token usage of the stream.
+    ///
+    /// The future resolves once the `chain-complete` event has been received by the
+    /// background task, before the consumer receives it, which suits callers displaying
+    /// the text while still needing the usage for billing.
+    ///
+    /// The task only gets there while the consumer keeps up, though: with
+    /// `SlowConsumerPolicy::Block`, it pauses reading the response while the buffer is
+    /// full, so a consumer that stops receiving events also holds the usage back. Use
+    /// `SlowConsumerPolicy::DropOldest` to get the usage without consuming the stream.
+    ///
+    /// # Returns
+    /// A future resolving to the usage of the `chain-complete` event. If the stream ends
+    /// without one, it resolves to the last usage reported, or `None` if there was none.
+    pub fn usage_future(&self) -> impl Future<Output = Option<Usage>> + Send + 'static {
+        let mut usage = self.usage.clone();
+
+        async move {
+            // Errors once the stream has ended, leaving the last reported usage.
+            let _ = usage.wait_for(Option::is_some).await;
+            let usage = usage.borrow().clone();
+            usage
+        }
+    }
+
+    /// Cancels the stream and returns the text received so far.
+    ///
+    /// The background task receiving the stream is aborted, and the text of every
+    /// `text-delta` received until then is returned, including deltas the consumer hasn't
+    /// received yet. This suits "stop generating" buttons that keep the partial output.
+    pub fn cancel_and_take_text(self) -> String {
+        self.task.abort();
+
+        let mut text = self.text.lock().unwrap_or_else(|e| e.into_inner());
+        std::mem::take(&mut *text)
+    }
+
+    /// Stops the stream, closing its connection.
+    ///
+    /// The background task receiving the stream is aborted, so no more bytes are read,
+    /// and the stream ends right away, dropping the events it buffered. Unlike dropping
+    /// the stream, this takes effect even while the server sends nothing.
+    pub fn abort(&mut self) {
+        self.task.abort();
+        self.aborted = true;
+    }
+
+    /// Cancels the stream, and the run generating it on the server, recording why.
+    ///
+    /// The background task receiving the stream is aborted right away, then the run is
+    /// cancelled with `Client::cancel_run`, so the reason shows up in server-side audits.
+    ///
+    /// # Arguments
+    /// * `reason` - Why the generation was stopped, e.g. `"user pressed stop"`.
+    ///
+    /// # Returns
+    /// `Ok(())` once the server cancelled the run, the error of the cancel request, or
+    /// `Error::ResponseFormatError` if the stream hadn't identified its conversation yet,
+    /// in which case it's only aborted locally.
+    pub async fn cancel_with_reason(self, reason: &str) -> Result<(), Error> {
+        self.task.abort();
+
+        let conversation = self.canceller.as_ref().and_then(|canceller| {
+            let conversation = canceller
+                .conversation
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            conversation.clone().map(|uuid| (&canceller.client, uuid))
+        });
+
+        match conversation {
+            Some((client, uuid)) => client.cancel_run(&uuid, Some(reason)).await,
+            None => Err(Error::ResponseFormatError(
+                "The stream hasn't identified its conversation yet".to_owned(),
+            )),
+        }
+    }
+
+    /// Receives the next event of the stream.
+    ///
+    /// # Returns
+    /// `Some(Ok(Event))` for every received event, `Some(Err(Error))` for an error met while
+    /// receiving the stream, or `None` once the stream has ended.
+    ///
+    /// An event whose payload fails to parse yields an `Error::SerializationError`, after
+    /// which the stream goes on. Any other error ends the stream.
+    ///
+    /// With `SlowConsumerPolicy::DropOldest`, events dropped while the consumer fell
+    /// behind are skipped.
+    pub async fn recv(&mut self) -> Option<Result<Event, Error>> {
+        self.next().await
+    }
+
+    /// Unpacks a received item, keeping its raw payload.
+    fn unpack(&mut self, item: StreamItem) -> Result<Event, Error> {
+        match item {
+            Ok((event, raw)) => {
+                self.raw = raw;
+                Ok(event)
+            }
+            Err(error) => {
+                self.raw = None;
+                Err(error.take())
+            }
+        }
+    }
+
+    /// Returns whether the stream ended early because the consumer fell behind.
+    ///
+    /// This only happens with `SlowConsumerPolicy::Error`, once the buffered events
+    /// have been received.
+    pub fn overflowed(&self) -> bool {
+        match &self.receiver {
+            EventReceiver::Error(_, overflowed) => overflowed.load(Ordering::Relaxed),
+            _ => false,
+        }
+    }
+
+    /// Returns the raw `data:` payload of the last received event.
+    ///
+    /// This is only available when the client was built with `capture_raw_events(true)`,
+    /// and is useful to diagnose events that don't parse as expected.
+    pub fn raw(&self) -> Option<&str> {
+        self.raw.as_deref()
+    }
+}
+
+impl Stream for EventStream {
+    type Item = Result<Event, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.aborted {
+            return Poll::Ready(None);
+        }
+
+        let item = match &mut self.receiver {
+            EventReceiver::Block(receiver) | EventReceiver::Error(receiver, _) => {
+                ready!(receiver.poll_recv(cx))
+            }
+            EventReceiver::DropOldest(receiver) => loop {
+                // Events dropped while the consumer fell behind are skipped.
+                match ready!(receiver.poll_next_unpin(cx)) {
+                    Some(Ok(item)) => break Some(item),
+                    Some(Err(_)) => continue,
+                    None => break None,
+                }
+            },
+        };
+
+        if item.is_none() && self.overflowed() {
+            // Report the overflow once, as the last item of the stream.
+            self.aborted = true;
+            return Poll::Ready(Some(Err(Error::Other(
+                "The stream consumer fell behind".to_owned(),
+            ))));
+        }
+
+        Poll::Ready(item.map(|item| self.unpack(item)))
+    }
 }
@@ -0,0 +1,228 @@
+use serde_json::{Map, Value};
+
+use crate::{
+    error::Error,
+    models::{
+        document::RunDocument,
+        options::Options,
+        response::{EventStream, Response},
+    },
+    Client,
+};
+
+/// A fluent request running a document, created with `Client::prompt`.
+///
+/// Parameters are collected one by one with `param`, and the request is sent with `send`,
+/// or `send_stream` for a stream of events. It builds a `RunDocument` under the hood, so
+/// it behaves exactly like `Client::run`.
+///
+/// ## Usage Example
+///
+/// ```rust,no_run
+/// use latitude_sdk::Client;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), latitude_sdk::error::Error> {
+///     let client = Client::builder("your_api_key".into()).project_id(123).build()?;
+///
+///     let response = client
+///         .prompt("Workers/EmotionAnalyzer")
+///         .param("user_message", "Hello, world!")
+///         .version("version-uuid")
+///         .send()
+///         .await;
+///
+///     Ok(())
+/// }
+/// ```
+pub struct RunRequestBuilder<'a> {
+    client: &'a Client,
+    path: String,
+    parameters: Map<String, Value>,
+    stream: bool,
+    options: Option<Options>,
+}
+
+impl<'a> RunRequestBuilder<'a> {
+    pub(crate) fn new(client: &'a Client, path: &str) -> Self {
+        Self {
+            client,
+            path: path.to_owned(),
+            parameters: Map::new(),
+            stream: false,
+            options: None,
+        }
+    }
+
+    /// Sets a parameter of the document, replacing any previous value of the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the parameter.
+    /// * `value` - The value of the parameter.
+    ///
+    /// # Returns
+    ///
+    /// The request with the parameter set.
+    pub fn param(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.parameters.insert(key.to_owned(), value.into());
+        self
+    }
+
+    /// Streams the response of `send` as events.
+    ///
+    /// # Returns
+    ///
+    /// The request with streaming enabled.
+    pub fn stream(mut self) -> Self {
+        self.stream = true;
+        self
+    }
+
+    /// Sets the version of the document to run, overriding the client's default.
+    ///
+    /// # Arguments
+    ///
+    /// * `version_id` - The UUID of the version, or `live`.
+    ///
+    /// # Returns
+    ///
+    /// The request with the version set.
+    pub fn version(mut self, version_id: &str) -> Self {
+        self.options.get_or_insert_with(Options::default).version_id = Some(version_id.to_owned());
+        self
+    }
+
+    /// Sets the options of the request, such as its project or timeout.
+    ///
+    /// Fields set by earlier calls, such as the version, are kept unless `options` sets
+    /// them too.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The options overriding the client's defaults.
+    ///
+    /// # Returns
+    ///
+    /// The request with the options set.
+    pub fn options(mut self, options: Options) -> Self {
+        self.options = Some(options.merge(&self.options.unwrap_or_default()));
+        self
+    }
+
+    /// Sends the request. See `Client::run`.
+    ///
+    /// # Returns
+    ///
+    /// A `Response::Json`, or a `Response::Stream` if `stream` was called.
+    pub async fn send(self) -> Result<Response, Error> {
+        let client = self.client;
+        client.run(self.into_document()).await
+    }
+
+    /// Sends the request, streaming its response.
+    ///
+    /// # Returns
+    ///
+    /// The `EventStream` of the response.
+    pub async fn send_stream(mut self) -> Result<EventStream, Error> {
+        self.stream = true;
+
+        match self.send().await? {
+            Response::Stream(events) => Ok(events),
+            Response::Json(_) => Err(Error::ResponseFormatError(
+                "Expected a streaming response".to_owned(),
+            )),
+        }
+    }
+
+    fn into_document(self) -> RunDocument<Map<String, Value>> {
+        let parameters = (!self.parameters.is_empty()).then_some(self.parameters);
+        RunDocument::new(self.path, parameters, self.stream, self.options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+    use httpmock::{Method::POST, MockServer};
+    use serde_json::json;
+    use tokio_stream::StreamExt;
+
+    #[tokio::test]
+    async fn test_prompt_send() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/12345/versions/pinned/documents/run")
+                .json_body(json!({
+                    "path": "test-path",
+                    "parameters": { "user_message": "Hello", "count": 2 },
+                    "stream": false
+                }));
+            then.status(200).json_body(json!({
+                "uuid": "conversation-uuid",
+                "response": {
+                    "text": "Hi there",
+                    "usage": {
+                        "prompt_tokens": 10,
+                        "completion_tokens": 20,
+                        "total_tokens": 30
+                    }
+                }
+            }));
+        });
+
+        let client = test_util::client(&server);
+        let response = client
+            .prompt("test-path")
+            .param("user_message", "Hello")
+            .param("count", 2)
+            .version("pinned")
+            .send()
+            .await;
+
+        match response {
+            Ok(Response::Json(response)) => assert_eq!(response.response.text, "Hi there"),
+            other => panic!("Expected a JSON response, got {:?}", other),
+        }
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_prompt_send_stream() {
+        let server = MockServer::start_async().await;
+        let mock = test_util::mock_run_stream(
+            &server,
+            "test-path",
+            &[
+                (
+                    "provider-event",
+                    r#"{"type":"text-delta","textDelta":"Hi"}"#,
+                ),
+                (
+                    "provider-event",
+                    r#"{"type":"text-delta","textDelta":" there"}"#,
+                ),
+            ],
+        );
+
+        let client = test_util::client(&server);
+        let mut events = client
+            .prompt("test-path")
+            .send_stream()
+            .await
+            .expect("Expected a stream");
+
+        let mut text = String::new();
+        while let Some(event) = events.next().await {
+            if let Some(delta) = event.unwrap().text_delta() {
+                text.push_str(delta);
+            }
+        }
+
+        assert_eq!(text, "Hi there");
+        mock.assert();
+    }
+}
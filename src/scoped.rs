@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    models::{
+        document::{Document, RunDocument},
+        log::{Log, LogResponse},
+        options::Options,
+        response::Response,
+    },
+    Client,
+};
+
+/// A lightweight view over a `Client` with its own default `Options`.
+///
+/// Created with `Client::with_options`, a `ScopedClient` shares the underlying client
+/// (and its connection pool) while overriding the project and version defaults. Calls use
+/// the scoped options unless a per-call override is given, in which case the fields set
+/// on the override take precedence. Cloning a `ScopedClient` is cheap.
+///
+/// ## Usage Example
+///
+/// ```
+/// use latitude_sdk::{Client, models::options::Options};
+///
+/// let client = Client::builder("your_api_key".into())
+///     .project_id(123)
+///     .build()?;
+///
+/// let staging = client.with_options(Options::new(Some("staging-uuid".into()), None));
+/// assert_eq!(staging.options().version_id.as_deref(), Some("staging-uuid"));
+/// # Ok::<(), latitude_sdk::error::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct ScopedClient<'a> {
+    client: &'a Client,
+    options: Arc<Options>,
+}
+
+impl<'a> ScopedClient<'a> {
+    pub(crate) fn new(client: &'a Client, options: Options) -> Self {
+        Self {
+            client,
+            options: Arc::new(options),
+        }
+    }
+
+    /// Returns the default options applied by this scoped client.
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// Runs a document using the scoped defaults. See `Client::run`.
+    ///
+    /// # Arguments
+    /// * `document` - The document to run; its `options` override the scoped defaults.
+    pub async fn run<T>(&self, mut document: RunDocument<T>) -> Result<Response, Error>
+    where
+        T: Serialize + std::fmt::Debug,
+    {
+        document.options = Some(self.scoped(document.options));
+        self.client.run(document).await
+    }
+
+    /// Fetches a document using the scoped defaults. See `Client::get`.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the document.
+    /// * `options` - Optional options overriding the scoped defaults.
+    pub async fn get(&self, path: &str, options: Option<Options>) -> Result<Document, Error> {
+        self.client.get(path, Some(self.scoped(options))).await
+    }
+
+    /// Creates a log using the scoped defaults. See `Client::log`.
+    ///
+    /// # Arguments
+    /// * `log` - The log to create; its `options` override the scoped defaults.
+    pub async fn log(&self, mut log: Log) -> Result<LogResponse, Error> {
+        log.options = Some(self.scoped(log.options));
+        self.client.log(log).await
+    }
+
+    fn scoped(&self, options: Option<Options>) -> Options {
+        match options {
+            Some(options) => options.merge(&self.options),
+            None => Options::clone(&self.options),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{Method::POST, MockServer};
+    use serde_json::json;
+
+    fn run_response() -> serde_json::Value {
+        json!({
+            "uuid": "123e4567-e89b-12d3-a456-426614174000",
+            "response": {
+                "text": "Test response",
+                "usage": {
+                    "prompt_tokens": 10,
+                    "completion_tokens": 20,
+                    "total_tokens": 30
+                }
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_scoped_defaults_apply() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/999/versions/pinned/documents/run");
+            then.status(200).json_body(run_response());
+        });
+
+        let client = Client::builder("test_api_key".into())
+            .project_id(12345)
+            .base_url(server.base_url())
+            .build()
+            .unwrap();
+        let scoped = client.with_options(Options::new(Some("pinned".into()), Some(999)));
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".into())
+            .build();
+
+        assert!(matches!(scoped.run(document).await, Ok(Response::Json(_))));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_per_call_options_override_scoped_defaults() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/projects/999/versions/override/documents/run");
+            then.status(200).json_body(run_response());
+        });
+
+        let client = Client::builder("test_api_key".into())
+            .project_id(12345)
+            .base_url(server.base_url())
+            .build()
+            .unwrap();
+        let scoped = client.with_options(Options::new(Some("pinned".into()), Some(999)));
+
+        let document = RunDocument::<()>::builder()
+            .path("test-path".into())
+            .options(Options::builder().version_id("override".into()).build())
+            .build();
+
+        assert!(matches!(scoped.run(document).await, Ok(Response::Json(_))));
+        mock.assert();
+    }
+}
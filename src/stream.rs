@@ -0,0 +1,340 @@
+use async_sse::decode;
+use bytes::Bytes;
+use futures_lite::{future::block_on, io::BufReader, AsyncRead, Stream, StreamExt};
+
+use crate::{error::Error, models::event::Event};
+
+/// SSE event name carrying Latitude events.
+pub(crate) const LATITUDE_EVENT: &str = "latitude-event";
+/// SSE event name carrying provider events.
+pub(crate) const PROVIDER_EVENT: &str = "provider-event";
+
+/// The SSE event names carrying Latitude and provider events.
+///
+/// They default to `latitude-event` and `provider-event`, and may be overridden for
+/// proxies renaming them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EventNames {
+    pub(crate) latitude: String,
+    pub(crate) provider: String,
+}
+
+impl Default for EventNames {
+    fn default() -> Self {
+        Self {
+            latitude: LATITUDE_EVENT.to_owned(),
+            provider: PROVIDER_EVENT.to_owned(),
+        }
+    }
+}
+
+/// UTF-8 byte order mark some proxies prepend to the stream.
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Strips any leading UTF-8 BOM and whitespace preceding the first SSE field.
+pub(crate) fn trim_prelude(mut bytes: &[u8]) -> &[u8] {
+    loop {
+        if let Some(rest) = bytes.strip_prefix(BOM) {
+            bytes = rest;
+        } else if let Some((first, rest)) = bytes.split_first() {
+            if !first.is_ascii_whitespace() {
+                return bytes;
+            }
+            bytes = rest;
+        } else {
+            return bytes;
+        }
+    }
+}
+
+/// Strips a leading UTF-8 BOM and whitespace from the start of a streamed body.
+///
+/// Chunks are trimmed until the first one containing an SSE field, after which the
+/// stream passes through untouched. A BOM split across chunks is held back until its
+/// last byte arrives.
+pub(crate) fn skip_prelude<S>(stream: S) -> impl Stream<Item = Result<Bytes, std::io::Error>>
+where
+    S: Stream<Item = Result<Bytes, std::io::Error>>,
+{
+    let mut in_prelude = true;
+    let mut partial_bom = Vec::new();
+
+    stream.map(move |chunk| {
+        chunk.map(|chunk| {
+            if !in_prelude {
+                return chunk;
+            }
+
+            let chunk = if partial_bom.is_empty() {
+                chunk
+            } else {
+                Bytes::from([std::mem::take(&mut partial_bom).as_slice(), &chunk].concat())
+            };
+
+            let rest = trim_prelude(&chunk);
+            if !rest.is_empty() && rest.len() < BOM.len() && BOM.starts_with(rest) {
+                partial_bom = rest.to_vec();
+                return Bytes::new();
+            }
+
+            let trimmed = rest.len();
+            in_prelude = trimmed == 0;
+            chunk.slice(chunk.len() - trimmed..)
+        })
+    })
+}
+
+/// Parses the payload of a named SSE message into an `Event`.
+///
+/// Messages with an unrecognized name are mapped to `Event::UnknownEvent`.
+pub(crate) fn parse_event(names: &EventNames, name: &str, data: &[u8]) -> Result<Event, Error> {
+    if name == names.latitude {
+        serde_json::from_slice(data)
+            .map(Event::LatitudeEvent)
+            .map_err(Error::from)
+    } else if name == names.provider {
+        serde_json::from_slice(data)
+            .map(Event::ProviderEvent)
+            .map_err(Error::from)
+    } else {
+        Ok(Event::UnknownEvent)
+    }
+}
+
+/// Decodes an SSE byte stream into the `Event`s it carries, alongside each raw payload.
+///
+/// As the SSE spec requires, an event split across several `data:` lines has them joined
+/// with newlines before it's parsed, and `:` comment lines are skipped.
+///
+/// Payloads that fail to parse yield an `Error::SerializationError` without ending the
+/// stream, while malformed SSE yields an `Error::Other`.
+pub(crate) fn decode_with_raw<R>(
+    reader: R,
+    names: EventNames,
+) -> impl Stream<Item = Result<(Event, Vec<u8>), Error>>
+where
+    R: AsyncRead + Unpin,
+{
+    decode(BufReader::new(reader)).filter_map(move |event| match event {
+        Ok(async_sse::Event::Message(message)) => Some(
+            parse_event(&names, message.name(), message.data())
+                .map(|event| (event, message.into_bytes())),
+        ),
+        Ok(async_sse::Event::Retry(_)) => None,
+        Err(e) => Some(Err(Error::Other(e.to_string()))),
+    })
+}
+
+/// Decodes an SSE byte stream into the `Event`s it carries.
+///
+/// This is the decoding behind streamed responses, without the background task and
+/// channel, so it can run on any reader, e.g. an in-memory capture.
+///
+/// # Arguments
+/// * `reader` - The SSE byte stream.
+///
+/// # Returns
+/// * A stream of the decoded events in order. A payload that fails to parse yields an
+///   error without ending the stream.
+///
+/// # Example
+/// ```
+/// use futures_lite::{future::block_on, StreamExt};
+/// use latitude_sdk::decode_stream;
+///
+/// let sse: &[u8] = b"event: provider-event\ndata: {\"type\":\"text-delta\",\"textDelta\":\"Hi\"}\n\n";
+/// let events = block_on(decode_stream(sse).collect::<Vec<_>>());
+///
+/// assert_eq!(events.len(), 1);
+/// ```
+pub fn decode_stream<R>(reader: R) -> impl Stream<Item = Result<Event, Error>>
+where
+    R: AsyncRead + Unpin,
+{
+    decode_with_raw(reader, EventNames::default()).map(|event| event.map(|(event, _)| event))
+}
+
+/// Decodes a raw SSE byte slice into the `Event`s it contains.
+///
+/// This runs the same name-based dispatch as a streamed response, which is useful for
+/// reprocessing SSE captured from logs or proxies.
+///
+/// # Arguments
+/// * `bytes` - The raw SSE text, e.g. `event: provider-event\ndata: {...}\n\n`.
+///
+/// # Returns
+/// * `Vec<Event>` - The decoded events in order, or the first decoding/parsing error.
+///
+/// # Example
+/// ```
+/// use latitude_sdk::decode_events;
+///
+/// let events = decode_events(b"event: provider-event\ndata: {\"type\":\"text-delta\",\"textDelta\":\"Hi\"}\n\n")
+///     .expect("Failed to decode events");
+///
+/// assert_eq!(events.len(), 1);
+/// ```
+pub fn decode_events(bytes: &[u8]) -> Result<Vec<Event>, Error> {
+    block_on(async {
+        let mut decoder = decode_stream(trim_prelude(bytes));
+        let mut events = vec![];
+
+        while let Some(event) = decoder.next().await {
+            events.push(event?);
+        }
+
+        Ok(events)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::event::{LatitudeEventType, ProviderEventType, TextDelta};
+
+    #[test]
+    fn test_skip_prelude_strips_split_bom() {
+        let chunks: Vec<&[u8]> = vec![
+            b"\xEF",
+            b"\xBB",
+            b"\xBF\nevent: provider-event\n",
+            b"data: {}\n\n",
+        ];
+        let stream = futures_lite::stream::iter(
+            chunks
+                .into_iter()
+                .map(|chunk| Ok(Bytes::from_static(chunk))),
+        );
+
+        let body = block_on(
+            skip_prelude(stream)
+                .map(|chunk| chunk.expect("Failed to read chunk"))
+                .collect::<Vec<_>>(),
+        )
+        .concat();
+
+        assert_eq!(body, b"event: provider-event\ndata: {}\n\n");
+    }
+
+    #[test]
+    fn test_decode_events_multi_event_blob() {
+        let blob = concat!(
+            "event: latitude-event\n",
+            r#"data: {"type":"chain-step","isLastStep":true,"config":{"provider":"Latitude","model":"gpt-4o-mini"},"messages":[],"uuid":"58e86f35-293c-4f12-a412-9915cb385850"}"#,
+            "\n\n",
+            "event: provider-event\n",
+            r#"data: {"type":"text-delta","textDelta":"Hello"}"#,
+            "\n\n",
+            "event: provider-event\n",
+            r#"data: {"type":"text-delta","textDelta":" world"}"#,
+            "\n\n",
+            "event: something-else\n",
+            "data: {}\n\n",
+        );
+
+        let events = decode_events(blob.as_bytes()).expect("Failed to decode events");
+
+        assert_eq!(events.len(), 4);
+        assert!(matches!(
+            &events[0],
+            Event::LatitudeEvent(event) if matches!(event.event_type, LatitudeEventType::ChainStep(_))
+        ));
+        assert!(matches!(
+            &events[1],
+            Event::ProviderEvent(event) if event.event_type == ProviderEventType::TextDelta(TextDelta {
+                text_delta: "Hello".to_owned()
+            })
+        ));
+        assert!(matches!(
+            &events[2],
+            Event::ProviderEvent(event) if event.event_type == ProviderEventType::TextDelta(TextDelta {
+                text_delta: " world".to_owned()
+            })
+        ));
+        assert_eq!(events[3], Event::UnknownEvent);
+    }
+
+    #[test]
+    fn test_decode_events_with_bom_and_leading_whitespace() {
+        let event =
+            "event: provider-event\ndata: {\"type\":\"text-delta\",\"textDelta\":\"Hi\"}\n\n";
+
+        for prefix in ["\u{feff}", "\u{feff}\n\n", "  \r\n\t\n", "\u{feff}  "] {
+            let body = format!("{}{}", prefix, event);
+            let events = decode_events(body.as_bytes()).expect("Failed to decode events");
+
+            assert_eq!(
+                events,
+                vec![Event::ProviderEvent(crate::models::event::ProviderEvent {
+                    event_type: ProviderEventType::TextDelta(TextDelta {
+                        text_delta: "Hi".to_owned()
+                    })
+                })],
+                "prefix {:?}",
+                prefix
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_events_multi_line_data_and_comments() {
+        let blob = concat!(
+            ": keep-alive\n",
+            "event: latitude-event\n",
+            r#"data: {"type":"chain-step","isLastStep":true,"#,
+            "\n",
+            ": comment between data lines\n",
+            r#"data: "config":{"provider":"Latitude","model":"gpt-4o-mini"},"messages":[],"uuid":"58e86f35-293c-4f12-a412-9915cb385850"}"#,
+            "\n\n",
+            ": keep-alive\n\n",
+            "event: provider-event\n",
+            r#"data: {"type":"text-delta","#,
+            "\n",
+            r#"data: "textDelta":"Hello"}"#,
+            "\n\n",
+        );
+
+        let events = decode_events(blob.as_bytes()).expect("Failed to decode events");
+
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[0],
+            Event::LatitudeEvent(event) if matches!(event.event_type, LatitudeEventType::ChainStep(_))
+        ));
+        assert_eq!(events[1].text_delta(), Some("Hello"));
+    }
+
+    #[test]
+    fn test_decode_events_invalid_payload() {
+        let result = decode_events(b"event: provider-event\ndata: not-json\n\n");
+
+        assert!(matches!(result, Err(Error::SerializationError(_))));
+    }
+
+    #[test]
+    fn test_decode_stream_in_memory() {
+        let sse = concat!(
+            "event: provider-event\n",
+            r#"data: {"type":"text-delta","textDelta":"Hello"}"#,
+            "\n\n",
+            "event: provider-event\n",
+            "data: not-json\n\n",
+            "event: provider-event\n",
+            r#"data: {"type":"text-delta","textDelta":" world"}"#,
+            "\n\n",
+        );
+
+        let events = block_on(decode_stream(sse.as_bytes()).collect::<Vec<_>>());
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[0].as_ref().ok().and_then(Event::text_delta),
+            Some("Hello")
+        );
+        assert!(matches!(events[1], Err(Error::SerializationError(_))));
+        assert_eq!(
+            events[2].as_ref().ok().and_then(Event::text_delta),
+            Some(" world")
+        );
+    }
+}
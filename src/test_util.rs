@@ -0,0 +1,109 @@
+//! Helpers for testing code that depends on the Latitude API against a mock server.
+//!
+//! Available with the `test-util` feature, these mock the run endpoint with JSON or
+//! event stream responses through [`httpmock`], so downstream tests don't need to
+//! reinvent them.
+//!
+//! # Example
+//!
+//! ```
+//! use latitude_sdk::{models::document::RunDocument, models::response::Response, test_util};
+//! use httpmock::MockServer;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let server = MockServer::start_async().await;
+//!     let mock = test_util::mock_run_json(&server, "Workers/EmotionAnalyzer", "Happy");
+//!
+//!     let client = test_util::client(&server);
+//!     let document = RunDocument::<()>::builder()
+//!         .path("Workers/EmotionAnalyzer".to_owned())
+//!         .build();
+//!
+//!     match client.run(document).await {
+//!         Ok(Response::Json(response)) => assert_eq!(response.response.text, "Happy"),
+//!         _ => panic!("Expected a JSON response"),
+//!     }
+//!
+//!     mock.assert();
+//! }
+//! ```
+
+use httpmock::{Method::POST, Mock, MockServer};
+use serde_json::json;
+
+use crate::Client;
+
+/// The API key of the client returned by `client`.
+pub const API_KEY: &str = "test_api_key";
+
+/// The project ID of the client returned by `client`, which the mocks expect.
+pub const PROJECT_ID: u64 = 12345;
+
+/// The UUID of the conversation returned by `mock_run_json`.
+pub const CONVERSATION_UUID: &str = "123e4567-e89b-12d3-a456-426614174000";
+
+/// Creates a `Client` sending its requests to the mock server.
+pub fn client(server: &MockServer) -> Client {
+    Client::builder(API_KEY.to_owned())
+        .project_id(PROJECT_ID)
+        .base_url(server.base_url())
+        .build()
+        .expect("Failed to build the mock client")
+}
+
+/// Mocks a non-streaming run of the document at `path`, responding with `text`.
+///
+/// The response reports 10 prompt tokens, 20 completion tokens and 30 total tokens.
+pub fn mock_run_json<'a>(server: &'a MockServer, path: &str, text: &str) -> Mock<'a> {
+    server.mock(|when, then| {
+        when.method(POST)
+            .path(run_path())
+            .header("authorization", format!("Bearer {}", API_KEY))
+            .json_body_partial(json!({ "path": path, "stream": false }).to_string());
+        then.status(200).json_body(json!({
+            "uuid": CONVERSATION_UUID,
+            "response": {
+                "text": text,
+                "usage": {
+                    "prompt_tokens": 10,
+                    "completion_tokens": 20,
+                    "total_tokens": 30
+                }
+            }
+        }));
+    })
+}
+
+/// Mocks a streaming run of the document at `path`, responding with the given events.
+///
+/// # Arguments
+/// * `events` - The `(event name, data)` pairs of the stream, e.g.
+///   `("provider-event", r#"{"type":"text-delta","textDelta":"Hi"}"#)`.
+pub fn mock_run_stream<'a>(
+    server: &'a MockServer,
+    path: &str,
+    events: &[(&str, &str)],
+) -> Mock<'a> {
+    server.mock(|when, then| {
+        when.method(POST)
+            .path(run_path())
+            .header("authorization", format!("Bearer {}", API_KEY))
+            .json_body_partial(json!({ "path": path, "stream": true }).to_string());
+        then.status(200)
+            .header("content-type", "text/event-stream")
+            .body(sse_body(events));
+    })
+}
+
+/// Formats `(event name, data)` pairs as an event stream body.
+pub fn sse_body(events: &[(&str, &str)]) -> String {
+    events
+        .iter()
+        .map(|(name, data)| format!("event: {}\ndata: {}\n\n", name, data))
+        .collect()
+}
+
+fn run_path() -> String {
+    format!("/projects/{}/versions/live/documents/run", PROJECT_ID)
+}
@@ -0,0 +1,68 @@
+use tiktoken_rs::{
+    cl100k_base_singleton, o200k_base_singleton, p50k_base_singleton, p50k_edit_singleton,
+    r50k_base_singleton, tokenizer::get_tokenizer, tokenizer::Tokenizer,
+};
+
+use crate::{error::Error, Client};
+
+impl Client {
+    /// Estimates locally how many tokens a text takes up for a model.
+    ///
+    /// The tokenizer is selected by the model family, e.g. `o200k_base` for `gpt-4o`
+    /// and `cl100k_base` for `gpt-4`, so large prompts can be budgeted before they're
+    /// run. The estimate covers the text only, not the per-message overhead providers add.
+    ///
+    /// # Arguments
+    /// * `text` - The text to count the tokens of.
+    /// * `model` - The name of the model, e.g. `gpt-4o-mini`.
+    ///
+    /// # Returns
+    /// * The number of tokens, or `Error::ConfigError` if no tokenizer is known for the model.
+    ///
+    /// # Example
+    /// ```
+    /// use latitude_sdk::Client;
+    ///
+    /// let tokens = Client::estimate_tokens("Hello, world!", "gpt-4o").unwrap();
+    /// assert_eq!(tokens, 4);
+    /// ```
+    pub fn estimate_tokens(text: &str, model: &str) -> Result<usize, Error> {
+        let bpe = match get_tokenizer(model) {
+            Some(Tokenizer::O200kBase) => o200k_base_singleton(),
+            Some(Tokenizer::Cl100kBase) => cl100k_base_singleton(),
+            Some(Tokenizer::P50kBase) => p50k_base_singleton(),
+            Some(Tokenizer::P50kEdit) => p50k_edit_singleton(),
+            Some(Tokenizer::R50kBase | Tokenizer::Gpt2) => r50k_base_singleton(),
+            None => {
+                return Err(Error::ConfigError(format!(
+                    "No tokenizer is known for model {}",
+                    model
+                )))
+            }
+        };
+
+        Ok(bpe.encode_ordinary(text).len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_tokens_known_models() {
+        let text = "The quick brown fox jumps over the lazy dog.";
+
+        assert_eq!(Client::estimate_tokens(text, "gpt-4o-mini").unwrap(), 10);
+        assert_eq!(Client::estimate_tokens(text, "gpt-4").unwrap(), 10);
+        assert_eq!(Client::estimate_tokens("", "gpt-4o").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_estimate_tokens_unknown_model() {
+        assert!(matches!(
+            Client::estimate_tokens("Hello", "some-unknown-model"),
+            Err(Error::ConfigError(_))
+        ));
+    }
+}